@@ -38,7 +38,7 @@ impl LuaPlugin {
     }
 
     async fn event_recv(mut self, mut event_receiver: nonebot_rs::EventReceiver) {
-        while let Ok(event) = event_receiver.recv().await {
+        while let Some(event) = nonebot_rs::plugin::recv_event(&mut event_receiver).await {
             match event {
                 Event::Message(m) => {
                     self.run_lua_scripts(m);
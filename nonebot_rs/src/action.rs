@@ -1,6 +1,6 @@
 use crate::ApiChannelItem;
 use colored::*;
-use tokio::sync::{mpsc, watch};
+use tokio::sync::mpsc;
 use tracing::{event, Level};
 
 /// Nonebot 内部设置项
@@ -11,14 +11,19 @@ pub enum Action {
         bot_id: String,
         api_sender: mpsc::Sender<ApiChannelItem>,
         action_sender: crate::ActionSender,
-        api_resp_watcher: watch::Receiver<crate::api_resp::ApiResp>,
+        api_resp_pending: crate::ApiRespPending,
     },
     /// 移除 Bot
-    RemoveBot { bot_id: String },
+    ///
+    /// api_resp_pending 标识发起移除的连接，Bot 已由新连接接管时不移除
+    RemoveBot {
+        bot_id: String,
+        api_resp_pending: crate::ApiRespPending,
+    },
     /// 变更 BotConfig
     ChangeBotConfig {
         bot_id: String,
-        bot_config: crate::config::BotConfig,
+        bot_config: Box<crate::config::BotConfig>,
     },
 }
 
@@ -31,14 +36,9 @@ impl crate::Nonebot {
                 bot_id,
                 api_sender,
                 action_sender,
-                api_resp_watcher,
+                api_resp_pending,
             } => {
-                let bot = self.add_bot(
-                    bot_id.clone(),
-                    api_sender,
-                    action_sender,
-                    api_resp_watcher.clone(),
-                );
+                let bot = self.add_bot(bot_id.clone(), api_sender, action_sender, api_resp_pending);
                 self.event_sender
                     .send(crate::event::Event::Nonebot(
                         crate::event::NbEvent::BotConnect { bot },
@@ -46,7 +46,20 @@ impl crate::Nonebot {
                     .unwrap();
                 event!(Level::DEBUG, "Add Bot [{}]", bot_id);
             }
-            Action::RemoveBot { bot_id } => {
+            Action::RemoveBot {
+                bot_id,
+                api_resp_pending,
+            } => {
+                if let Some(bot) = self.bots.get(&bot_id) {
+                    if !std::sync::Arc::ptr_eq(&bot.api_resp_pending, &api_resp_pending) {
+                        event!(
+                            Level::DEBUG,
+                            "Bot [{}] reconnected, ignore removing stale connection",
+                            bot_id.bright_red()
+                        );
+                        return;
+                    }
+                }
                 let bot = self.remove_bot(bot_id.clone());
                 match bot {
                     Some(bot) => {
@@ -68,7 +81,8 @@ impl crate::Nonebot {
             }
             Action::ChangeBotConfig { bot_id, bot_config } => {
                 let bot = self.bots.get_mut(&bot_id).unwrap();
-                bot.config = bot_config;
+                bot.send_queue.set_config(bot_config.throttle.clone());
+                bot.config = *bot_config;
             }
         }
     }
@@ -1,8 +1,9 @@
-use serde::{Deserialize, Serialize};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// Onebot Api 定义
 #[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(tag = "action")]
+#[serde(tag = "action", remote = "Self")]
 pub enum Api {
     #[serde(rename = "send_private_msg")]
     SendPrivateMsg {
@@ -96,7 +97,10 @@ pub enum Api {
     #[serde(rename = "get_csrf_token")]
     GetCsrfToken { params: Option<i8>, echo: String },
     #[serde(rename = "get_credentials")]
-    GetCredentials { params: GetCookies, echo: String },
+    GetCredentials {
+        params: GetCredentials,
+        echo: String,
+    },
     #[serde(rename = "get_record")]
     GetRecord { params: GetRecord, echo: String },
     #[serde(rename = "get_image")]
@@ -113,6 +117,112 @@ pub enum Api {
     SetRestart { params: SetRestart, echo: String },
     #[serde(rename = "clean_cache")]
     CleanCache { params: Option<i8>, echo: String },
+
+    // go-cqhttp 扩展 Api
+    #[serde(rename = "send_group_forward_msg")]
+    SendGroupForwardMsg {
+        params: SendGroupForwardMsg,
+        echo: String,
+    },
+    #[serde(rename = "send_private_forward_msg")]
+    SendPrivateForwardMsg {
+        params: SendPrivateForwardMsg,
+        echo: String,
+    },
+    #[serde(rename = "get_group_msg_history")]
+    GetGroupMsgHistory {
+        params: GetGroupMsgHistory,
+        echo: String,
+    },
+    #[serde(rename = "upload_group_file")]
+    UploadGroupFile {
+        params: UploadGroupFile,
+        echo: String,
+    },
+    #[serde(rename = "get_group_file_system_info")]
+    GetGroupFileSystemInfo {
+        params: GetGroupFileSystemInfo,
+        echo: String,
+    },
+    #[serde(rename = "set_essence_msg")]
+    SetEssenceMsg { params: SetEssenceMsg, echo: String },
+    #[serde(rename = "get_essence_msg_list")]
+    GetEssenceMsgList {
+        params: GetEssenceMsgList,
+        echo: String,
+    },
+    #[serde(rename = "ocr_image")]
+    OcrImage { params: OcrImage, echo: String },
+    #[serde(rename = "get_group_at_all_remain")]
+    GetGroupAtAllRemain {
+        params: GetGroupAtAllRemain,
+        echo: String,
+    },
+    #[serde(rename = "mark_msg_as_read")]
+    MarkMsgAsRead { params: MarkMsgAsRead, echo: String },
+    #[serde(rename = "set_group_portrait")]
+    SetGroupPortrait {
+        params: SetGroupPortrait,
+        echo: String,
+    },
+    #[serde(rename = "get_online_clients")]
+    GetOnlineClients {
+        params: GetOnlineClients,
+        echo: String,
+    },
+
+    /// 未定义的 Api，action 为 Onebot 实现端的 Api 名称
+    #[serde(skip)]
+    Custom {
+        // 由 Serialize 实现写入 action，此处改名避免与 tag 冲突
+        #[serde(rename = "custom_action")]
+        action: String,
+        params: serde_json::Value,
+        echo: String,
+    },
+}
+
+impl Serialize for Api {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Api::Custom {
+                action,
+                params,
+                echo,
+            } => {
+                let mut state = serializer.serialize_struct("Api", 3)?;
+                state.serialize_field("action", action)?;
+                state.serialize_field("params", params)?;
+                state.serialize_field("echo", echo)?;
+                state.end()
+            }
+            _ => Api::serialize(self, serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Api {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        if let Ok(api) = Api::deserialize(&value) {
+            return Ok(api);
+        }
+        // 未定义或参数不符的 Api 保留原始参数
+        match value["action"].as_str() {
+            Some(action) => Ok(Api::Custom {
+                action: action.to_string(),
+                params: value["params"].clone(),
+                echo: value["echo"].as_str().unwrap_or_default().to_string(),
+            }),
+            None => Err(serde::de::Error::missing_field("action")),
+        }
+    }
 }
 
 macro_rules! echos {
@@ -123,6 +233,7 @@ macro_rules! echos {
                     params: _,
                     echo: echo,
                 } => echo.clone(),)*
+                Api::Custom { echo, .. } => echo.clone(),
             }
         }
     };
@@ -133,7 +244,7 @@ macro_rules! no_params_builder {
         $(pub fn $fn_name() -> Api {
             Api::$api_type {
                 params: None,
-                echo: format!("{}-{}", stringify!($api_type), crate::utils::timestamp()),
+                echo: crate::utils::gen_echo(stringify!($api_type)),
             }
         })*
     };
@@ -144,7 +255,7 @@ macro_rules! params_builder {
         $(pub fn $fn_name(params: $api_type) -> Api {
             Api::$api_type {
                 params: params,
-                echo: format!("{}-{}", stringify!($api_type), crate::utils::timestamp()),
+                echo: crate::utils::gen_echo(stringify!($api_type)),
             }
         })*
     };
@@ -193,7 +304,19 @@ impl Api {
         GetStatus,
         GetVersionInfo,
         SetRestart,
-        CleanCache
+        CleanCache,
+        SendGroupForwardMsg,
+        SendPrivateForwardMsg,
+        GetGroupMsgHistory,
+        UploadGroupFile,
+        GetGroupFileSystemInfo,
+        SetEssenceMsg,
+        GetEssenceMsgList,
+        OcrImage,
+        GetGroupAtAllRemain,
+        MarkMsgAsRead,
+        SetGroupPortrait,
+        GetOnlineClients
     );
 
     // pub fn get_group_list() -> Api {
@@ -246,11 +369,32 @@ impl Api {
         (get_group_member_list, GetGroupMemberList),
         (get_group_honor_info, GetGroupHonorInfo),
         (get_cookies, GetCookies),
-        (get_credentials, GetCookies),
+        (get_credentials, GetCredentials),
         (get_record, GetRecord),
         (get_image, GetImage),
-        (set_restart, SetRestart)
+        (set_restart, SetRestart),
+        (send_group_forward_msg, SendGroupForwardMsg),
+        (send_private_forward_msg, SendPrivateForwardMsg),
+        (get_group_msg_history, GetGroupMsgHistory),
+        (upload_group_file, UploadGroupFile),
+        (get_group_file_system_info, GetGroupFileSystemInfo),
+        (set_essence_msg, SetEssenceMsg),
+        (get_essence_msg_list, GetEssenceMsgList),
+        (ocr_image, OcrImage),
+        (get_group_at_all_remain, GetGroupAtAllRemain),
+        (mark_msg_as_read, MarkMsgAsRead),
+        (set_group_portrait, SetGroupPortrait),
+        (get_online_clients, GetOnlineClients)
     );
+
+    /// 构建未定义的 Api
+    pub fn custom(action: &str, params: serde_json::Value) -> Api {
+        Api::Custom {
+            action: action.to_string(),
+            params,
+            echo: crate::utils::gen_echo(action),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -416,6 +560,11 @@ pub struct GetCookies {
     pub domain: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetCredentials {
+    pub domain: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GetRecord {
     pub file: String,
@@ -431,3 +580,112 @@ pub struct GetImage {
 pub struct SetRestart {
     pub delay: i64,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SendGroupForwardMsg {
+    pub group_id: String,
+    /// 合并转发节点 `Message::Node`
+    pub messages: Vec<crate::message::Message>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SendPrivateForwardMsg {
+    pub user_id: String,
+    /// 合并转发节点 `Message::Node`
+    pub messages: Vec<crate::message::Message>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetGroupMsgHistory {
+    /// 起始消息序号，None 时从最新消息开始
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_seq: Option<i64>,
+    pub group_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UploadGroupFile {
+    pub group_id: String,
+    /// 本地文件路径
+    pub file: String,
+    /// 储存名称
+    pub name: String,
+    /// 父目录 ID，None 时上传到根目录
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folder: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetGroupFileSystemInfo {
+    pub group_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SetEssenceMsg {
+    pub message_id: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetEssenceMsgList {
+    pub group_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OcrImage {
+    /// 图片 ID
+    pub image: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetGroupAtAllRemain {
+    pub group_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MarkMsgAsRead {
+    pub message_id: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SetGroupPortrait {
+    pub group_id: String,
+    /// 图片文件，支持 file:// http:// base64://
+    pub file: String,
+    /// 是否使用已缓存的文件，0 不使用 1 使用
+    pub cache: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetOnlineClients {
+    pub no_cache: bool,
+}
+
+#[test]
+fn credentials_api_test() {
+    let api = Api::get_credentials(GetCredentials {
+        domain: "qun.qq.com".to_string(),
+    });
+    assert!(matches!(api, Api::GetCredentials { .. }));
+    let json = serde_json::to_value(&api).unwrap();
+    assert_eq!(json["action"], "get_credentials");
+    assert_eq!(json["params"]["domain"], "qun.qq.com");
+}
+
+#[test]
+fn custom_api_test() {
+    let api = Api::custom("get_word_slices", serde_json::json!({ "content": "你好" }));
+    let json = serde_json::to_value(&api).unwrap();
+    assert_eq!(json["action"], "get_word_slices");
+    assert_eq!(json["params"]["content"], "你好");
+    assert_eq!(json["echo"], api.get_echo());
+
+    match serde_json::from_value(json).unwrap() {
+        Api::Custom { action, .. } => assert_eq!(action, "get_word_slices"),
+        api => panic!("unexpected api {:?}", api),
+    }
+    let json = serde_json::to_value(Api::get_status()).unwrap();
+    assert!(matches!(
+        serde_json::from_value(json).unwrap(),
+        Api::GetStatus { .. }
+    ));
+}
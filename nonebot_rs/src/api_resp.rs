@@ -3,11 +3,147 @@ use serde::{Deserialize, Serialize};
 
 /// Onebot Api 响应根结构体
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(from = "RawApiResp")]
 pub struct ApiResp {
     pub status: String,
     pub retcode: i32,
     pub data: RespData,
     pub echo: String,
+    /// 错误信息
+    #[serde(default)]
+    pub msg: Option<String>,
+    /// 错误信息（人类可读）
+    #[serde(default)]
+    pub wording: Option<String>,
+    /// 原始 data 字段
+    #[serde(skip_serializing)]
+    pub raw_data: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct RawApiResp {
+    status: String,
+    retcode: i32,
+    #[serde(default)]
+    data: serde_json::Value,
+    echo: String,
+    #[serde(default)]
+    msg: Option<String>,
+    #[serde(default)]
+    wording: Option<String>,
+}
+
+impl From<RawApiResp> for ApiResp {
+    fn from(raw: RawApiResp) -> Self {
+        let data = RespData::deserialize(&raw.data)
+            .unwrap_or_else(|_| RespData::Unknown(raw.data.clone()));
+        ApiResp {
+            status: raw.status,
+            retcode: raw.retcode,
+            data,
+            echo: raw.echo,
+            msg: raw.msg,
+            wording: raw.wording,
+            raw_data: raw.data,
+        }
+    }
+}
+
+impl ApiResp {
+    /// 是否调用成功（retcode 0 成功，1 已提交异步处理）
+    pub fn is_ok(&self) -> bool {
+        self.retcode == 0 || self.retcode == 1
+    }
+
+    /// 按类型解析原始 data 字段
+    #[allow(clippy::result_large_err)]
+    pub fn parse_data<T>(self) -> Result<T, ApiError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let ApiResp { data, raw_data, .. } = self;
+        serde_json::from_value(raw_data).map_err(|_| ApiError::UnexpectedData(data))
+    }
+}
+
+/// Onebot Api 调用错误
+#[derive(Debug, Clone)]
+pub enum ApiError {
+    /// Onebot 实现端返回失败
+    Failed {
+        retcode: i32,
+        status: String,
+        msg: Option<String>,
+        wording: Option<String>,
+    },
+    /// 等待 ApiResp 超时
+    Timeout,
+    /// Bot 连接已断开
+    Disconnected,
+    /// 响应 data 不符合预期类型
+    UnexpectedData(RespData),
+    /// 使用未绑定 Bot 的 Matcher 调用 Api
+    UnbuiltMatcher,
+    /// HTTP Api 请求失败或响应无法解析
+    Http(String),
+    /// 无法处理的未知请求类型
+    UnknownRequest,
+    /// Api 参数序列化失败
+    InvalidParams(String),
+}
+
+impl From<ApiResp> for ApiError {
+    fn from(resp: ApiResp) -> Self {
+        ApiError::Failed {
+            retcode: resp.retcode,
+            status: resp.status,
+            msg: resp.msg,
+            wording: resp.wording,
+        }
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiError::Failed {
+                retcode,
+                status,
+                msg,
+                wording,
+            } => write!(
+                f,
+                "Api failed retcode:{} status:{} msg:{} wording:{}",
+                retcode,
+                status,
+                msg.as_deref().unwrap_or_default(),
+                wording.as_deref().unwrap_or_default()
+            ),
+            ApiError::Timeout => write!(f, "Api timeout"),
+            ApiError::Disconnected => write!(f, "Bot disconnected"),
+            ApiError::UnexpectedData(data) => write!(f, "Unexpected resp data {:?}", data),
+            ApiError::UnbuiltMatcher => write!(f, "Calling api with unbuilt matcher"),
+            ApiError::Http(e) => write!(f, "HTTP api failed {}", e),
+            ApiError::UnknownRequest => write!(f, "Unknown request type"),
+            ApiError::InvalidParams(e) => write!(f, "Invalid api params {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+#[test]
+fn failed_resp_test() {
+    let test_str = "{\"data\":null,\"echo\":\"GetMsg-1631193409-0\",\"msg\":\"MSG_NOT_FOUND\",\"retcode\":100,\"status\":\"failed\",\"wording\":\"消息不存在\"}";
+    let resp: ApiResp = serde_json::from_str(test_str).unwrap();
+    assert!(!resp.is_ok());
+    match ApiError::from(resp) {
+        ApiError::Failed { retcode, msg, .. } => {
+            assert_eq!(retcode, 100);
+            assert_eq!(msg.as_deref(), Some("MSG_NOT_FOUND"));
+        }
+        e => panic!("unexpected error {}", e),
+    }
 }
 
 // impl ApiResp {
@@ -42,6 +178,14 @@ pub enum RespData {
     SendCheck(SendCheck),
     Status(crate::event::Status),
     VersionInfo(VersionInfo),
+    MessageHistory(MessageHistory),
+    GroupFileSystemInfo(GroupFileSystemInfo),
+    EssenceMsgList(Vec<EssenceMsg>),
+    OcrImage(OcrImage),
+    GroupAtAllRemain(GroupAtAllRemain),
+    OnlineClients(OnlineClients),
+    /// 未定义的响应数据
+    Unknown(serde_json::Value),
 }
 
 /// message_id 响应数据
@@ -58,12 +202,14 @@ pub struct Message {
     pub message_id: i32,
     pub real_id: i32,
     pub sender: Sender,
+    #[serde(deserialize_with = "crate::message::message_deserializer")]
     pub message: Vec<crate::message::Message>,
 }
 
 /// get_forward_msg 响应数据
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Messages {
+    #[serde(deserialize_with = "crate::message::message_deserializer")]
     pub message: Vec<crate::message::Message>,
 }
 
@@ -169,6 +315,90 @@ pub struct VersionInfo {
     pub protocol_version: String,
 }
 
+/// get_group_msg_history 响应数据
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MessageHistory {
+    pub messages: Vec<crate::event::GroupMessageEvent>,
+}
+
+/// get_group_file_system_info 响应数据
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GroupFileSystemInfo {
+    /// 文件总数
+    pub file_count: i32,
+    /// 文件上限
+    pub limit_count: i32,
+    /// 已使用空间
+    pub used_space: i64,
+    /// 空间上限
+    pub total_space: i64,
+}
+
+/// get_essence_msg_list 响应数组成员
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EssenceMsg {
+    #[serde(deserialize_with = "id_deserializer")]
+    pub sender_id: String,
+    pub sender_nick: String,
+    /// 消息发送时间
+    pub sender_time: i64,
+    #[serde(deserialize_with = "id_deserializer")]
+    pub operator_id: String,
+    pub operator_nick: String,
+    /// 设为精华时间
+    pub operator_time: i64,
+    pub message_id: i32,
+}
+
+/// ocr_image 响应数据
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OcrImage {
+    pub texts: Vec<TextDetection>,
+    pub language: String,
+}
+
+/// ocr_image 识别结果
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TextDetection {
+    pub text: String,
+    /// 置信度
+    pub confidence: i32,
+    /// 坐标
+    pub coordinates: Vec<Point>,
+}
+
+/// ocr_image 坐标
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// get_group_at_all_remain 响应数据
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GroupAtAllRemain {
+    /// 是否可以 @全体成员
+    pub can_at_all: bool,
+    /// 群内所有管理当天剩余 @全体成员 次数
+    pub remain_at_all_count_for_group: i32,
+    /// Bot 当天剩余 @全体成员 次数
+    pub remain_at_all_count_for_uin: i32,
+}
+
+/// get_online_clients 响应数据
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OnlineClients {
+    pub clients: Vec<Device>,
+}
+
+/// get_online_clients 在线客户端
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Device {
+    pub app_id: i64,
+    pub device_name: String,
+    pub device_kind: String,
+}
+
 /// get_friend_list 响应数组成员
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct FriendListItem {
@@ -233,3 +463,28 @@ pub enum Sender {
     Group(crate::event::GroupSender),
     Private(crate::event::PrivateSender),
 }
+
+#[test]
+fn gocq_resp_test() {
+    let test_str = "{\"data\":{\"can_at_all\":true,\"remain_at_all_count_for_group\":10,\"remain_at_all_count_for_uin\":5},\"echo\":\"GetGroupAtAllRemain-1631193409-0\",\"retcode\":0,\"status\":\"ok\"}";
+    let resp: ApiResp = serde_json::from_str(test_str).unwrap();
+    match resp.data {
+        RespData::GroupAtAllRemain(remain) => assert_eq!(remain.remain_at_all_count_for_uin, 5),
+        data => panic!("unexpected data {:?}", data),
+    }
+}
+
+#[test]
+fn unknown_resp_test() {
+    let test_str = "{\"data\":{\"slices\":[\"你\",\"好\"]},\"echo\":\"get_word_slices-1631193409-0\",\"retcode\":0,\"status\":\"ok\"}";
+    let resp: ApiResp = serde_json::from_str(test_str).unwrap();
+    assert!(matches!(resp.data, RespData::Unknown(_)));
+    assert_eq!(resp.raw_data["slices"][1], "好");
+
+    // get_msg 响应同样可解析为 MessageId，按原始 data 解析为目标类型
+    let test_str = "{\"data\":{\"message_id\":1,\"real_id\":1,\"time\":0},\"echo\":\"GetMsg-1631193409-0\",\"retcode\":0,\"status\":\"ok\"}";
+    let resp: ApiResp = serde_json::from_str(test_str).unwrap();
+    assert!(matches!(resp.data, RespData::MessageId(_)));
+    let data: serde_json::Value = resp.parse_data().unwrap();
+    assert_eq!(data["real_id"], 1);
+}
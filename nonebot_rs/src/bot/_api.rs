@@ -1,61 +1,91 @@
-use crate::{api, api_resp, RespData};
+use crate::{api, api_resp, ApiError, RespData};
+use colored::*;
+use tracing::{event, Level};
 
 macro_rules! no_resp_api {
-    ($fn_name: ident, $struct_name: tt, $param: ident: $param_type: ty) => {
-        pub async fn $fn_name(&self, $param: $param_type) {
-            self.call_api(api::Api::$fn_name(api::$struct_name { $param: $param }))
-                .await;
-        }
-    };
     ($fn_name: ident, $struct_name: tt, $($param: ident: $param_type: ty),*) => {
         pub async fn $fn_name(&self, $($param: $param_type,)*) {
             self.call_api(api::Api::$fn_name(api::$struct_name {
-                $($param: $param,)*
+                $($param,)*
             })).await;
         }
+
+        paste::paste! {
+            /// 等待 Onebot 返回，调用失败返回对应 ApiError
+            pub async fn [<try_ $fn_name>](&self, $($param: $param_type,)*) -> Result<(), ApiError> {
+                self.call_typed_api_resp(api::Api::$fn_name(api::$struct_name {
+                    $($param,)*
+                }))
+                .await
+                .map(|_| ())
+            }
+        }
     };
 }
 
 macro_rules! resp_api {
     ($fn_name: ident,$resp_data: tt, $resp_data_type: ty) => {
         pub async fn $fn_name(&self) -> Option<$resp_data_type> {
-            let resp = self.call_api_resp(api::Api::$fn_name()).await;
-            if let RespData::$resp_data(d) = resp.unwrap().data {
-                Some(d)
-            } else {
-                None
+            paste::paste! {
+                self.log_api_error(stringify!($fn_name), self.[<try_ $fn_name>]().await)
             }
         }
-    };
-    ($fn_name: ident, $struct_name: tt, $resp_data: tt, $resp_data_type: ty, $param: ident: $param_type: ty) => {
-        pub async fn $fn_name(&self, $param: $param_type) -> Option<$resp_data_type> {
-            let resp = self
-                .call_api_resp(api::Api::$fn_name(api::$struct_name { $param: $param }))
-                .await;
-            if let RespData::$resp_data(d) = resp.unwrap().data {
-                Some(d)
-            } else {
-                None
+
+        paste::paste! {
+            /// 调用失败返回对应 ApiError
+            pub async fn [<try_ $fn_name>](&self) -> Result<$resp_data_type, ApiError> {
+                let resp = self.call_typed_api_resp(api::Api::$fn_name()).await?;
+                match resp.data {
+                    RespData::$resp_data(d) => Ok(d),
+                    // untagged 解析可能匹配到字段相同的其他类型
+                    _ => resp.parse_data(),
+                }
             }
         }
     };
     ($fn_name: ident, $struct_name: tt, $resp_data: tt, $resp_data_type: ty, $($param: ident: $param_type: ty),*) => {
         pub async fn $fn_name(&self, $($param: $param_type,)*) -> Option<$resp_data_type> {
-            let resp = self
-                .call_api_resp(api::Api::$fn_name(api::$struct_name {
-                    $($param: $param,)*
-                }))
-                .await;
-            if let RespData::$resp_data(d) = resp.unwrap().data {
-                Some(d)
-            } else {
-                None
+            paste::paste! {
+                self.log_api_error(stringify!($fn_name), self.[<try_ $fn_name>]($($param,)*).await)
+            }
+        }
+
+        paste::paste! {
+            /// 调用失败返回对应 ApiError
+            pub async fn [<try_ $fn_name>](&self, $($param: $param_type,)*) -> Result<$resp_data_type, ApiError> {
+                let resp = self
+                    .call_typed_api_resp(api::Api::$fn_name(api::$struct_name {
+                        $($param,)*
+                    }))
+                    .await?;
+                match resp.data {
+                    RespData::$resp_data(d) => Ok(d),
+                    // untagged 解析可能匹配到字段相同的其他类型
+                    _ => resp.parse_data(),
+                }
             }
         }
     };
 }
 
 impl super::Bot {
+    /// 记录 ApiError 并转为 Option
+    fn log_api_error<T>(&self, api_name: &str, result: Result<T, ApiError>) -> Option<T> {
+        match result {
+            Ok(d) => Some(d),
+            Err(e) => {
+                event!(
+                    Level::WARN,
+                    "Bot [{}] Api {} {}",
+                    self.config.bot_id.red(),
+                    api_name.blue(),
+                    e
+                );
+                None
+            }
+        }
+    }
+
     // pub async fn delete_msg(&self, message_id: i32) {
     //     self.call_api(Api::delete_msg(api::DeleteMsg {
     //         message_id: message_id,
@@ -148,6 +178,23 @@ impl super::Bot {
         reason: String
     );
     no_resp_api!(set_restart, SetRestart, delay: i64);
+    no_resp_api!(
+        upload_group_file,
+        UploadGroupFile,
+        group_id: String,
+        file: String,
+        name: String,
+        folder: Option<String>
+    );
+    no_resp_api!(set_essence_msg, SetEssenceMsg, message_id: i32);
+    no_resp_api!(mark_msg_as_read, MarkMsgAsRead, message_id: i32);
+    no_resp_api!(
+        set_group_portrait,
+        SetGroupPortrait,
+        group_id: String,
+        file: String,
+        cache: i32
+    );
 
     // 获取消息
     // pub async fn get_msg(&self, message_id: i32) -> Option<api_resp::Message> {
@@ -234,7 +281,7 @@ impl super::Bot {
     resp_api!(get_csrf_token, ScrfToken, api_resp::ScrfToken);
     resp_api!(
         get_credentials,
-        GetCookies,
+        GetCredentials,
         Credentials,
         api_resp::Credentials,
         domain: String
@@ -252,4 +299,63 @@ impl super::Bot {
     resp_api!(can_send_image, SendCheck, api_resp::SendCheck);
     resp_api!(get_status, Status, crate::event::Status);
     resp_api!(get_version_info, VersionInfo, api_resp::VersionInfo);
+    resp_api!(
+        send_group_forward_msg,
+        SendGroupForwardMsg,
+        MessageId,
+        api_resp::MessageId,
+        group_id: String,
+        messages: Vec<crate::Message>
+    );
+    resp_api!(
+        send_private_forward_msg,
+        SendPrivateForwardMsg,
+        MessageId,
+        api_resp::MessageId,
+        user_id: String,
+        messages: Vec<crate::Message>
+    );
+    resp_api!(
+        get_group_msg_history,
+        GetGroupMsgHistory,
+        MessageHistory,
+        api_resp::MessageHistory,
+        message_seq: Option<i64>,
+        group_id: String
+    );
+    resp_api!(
+        get_group_file_system_info,
+        GetGroupFileSystemInfo,
+        GroupFileSystemInfo,
+        api_resp::GroupFileSystemInfo,
+        group_id: String
+    );
+    resp_api!(
+        get_essence_msg_list,
+        GetEssenceMsgList,
+        EssenceMsgList,
+        Vec<api_resp::EssenceMsg>,
+        group_id: String
+    );
+    resp_api!(
+        ocr_image,
+        OcrImage,
+        OcrImage,
+        api_resp::OcrImage,
+        image: String
+    );
+    resp_api!(
+        get_group_at_all_remain,
+        GetGroupAtAllRemain,
+        GroupAtAllRemain,
+        api_resp::GroupAtAllRemain,
+        group_id: String
+    );
+    resp_api!(
+        get_online_clients,
+        GetOnlineClients,
+        OnlineClients,
+        api_resp::OnlineClients,
+        no_cache: bool
+    );
 }
@@ -1,11 +1,17 @@
 use crate::api_resp;
-use crate::event::MessageEvent;
-use crate::{api, config, message, utils, ApiChannelItem, ApiResp};
+use crate::event::{MessageEvent, Request, RequestEvent};
+use crate::{api, config, message, ApiChannelItem, ApiError, ApiRespPending, RespData};
 use colored::*;
-use tokio::sync::{mpsc, watch};
+use tokio::sync::{mpsc, oneshot};
 use tracing::{event, Level};
 
 mod _api;
+/// 消息发送队列
+pub mod queue;
+
+/// 合并转发发送结果，失败时包含已发送部分的消息 ID
+pub type ForwardResult =
+    Result<Vec<api_resp::MessageId>, (Vec<api_resp::MessageId>, ApiError)>;
 
 /// 为 Plugin 提供各类 Onebot Api 
 #[derive(Debug, Clone)]
@@ -20,8 +26,12 @@ pub struct Bot {
     pub api_sender: mpsc::Sender<ApiChannelItem>,
     /// Nonebot Action Sender
     pub action_sender: crate::ActionSender,
-    /// ApiResp Receiver
-    pub api_resp_watcher: watch::Receiver<ApiResp>,
+    /// 等待 ApiResp 的请求表
+    pub api_resp_pending: ApiRespPending,
+    /// 数据存储后端
+    pub storage: crate::storage::SharedStorage,
+    /// 消息发送队列
+    pub send_queue: queue::SendQueue,
 }
 
 impl Bot {
@@ -30,30 +40,48 @@ impl Bot {
         config: config::BotConfig,
         api_sender: mpsc::Sender<ApiChannelItem>,
         action_sender: crate::ActionSender,
-        api_resp_watcher: watch::Receiver<ApiResp>,
+        api_resp_pending: ApiRespPending,
+        storage: crate::storage::SharedStorage,
     ) -> Self {
         Bot {
             bot_id: bot_id,
             connect_time: crate::utils::timestamp(),
+            send_queue: queue::SendQueue::new(config.throttle.clone(), api_sender.clone()),
             config: config,
             api_sender: api_sender,
             action_sender: action_sender,
-            api_resp_watcher: api_resp_watcher,
+            api_resp_pending,
+            storage,
         }
     }
 
+    /// 获取 namespace 对应的存储句柄，Plugin 可使用 `plugin_name()` 作为 namespace
+    pub fn storage(&self, namespace: &str) -> crate::storage::Namespace {
+        crate::storage::Namespace::new(self.storage.clone(), namespace)
+    }
+
     /// Send Group Msg
     pub async fn send_group_msg(&self, group_id: &str, msg: Vec<message::Message>) {
-        self.api_sender
-            .send(ApiChannelItem::Api(crate::api::Api::send_group_msg(
-                crate::api::SendGroupMsg {
+        if self
+            .queue_api(
+                crate::api::Api::send_group_msg(crate::api::SendGroupMsg {
                     group_id: group_id.to_string(),
                     message: msg.clone(),
                     auto_escape: false,
-                },
-            )))
+                }),
+                queue::Priority::Normal,
+            )
             .await
-            .unwrap();
+            .is_err()
+        {
+            event!(
+                Level::ERROR,
+                "Bot [{}] {}",
+                self.config.bot_id.red(),
+                ApiError::Disconnected
+            );
+            return;
+        }
         event!(
             Level::INFO,
             "Bot [{}] Send {:?} to Group ({})",
@@ -65,16 +93,26 @@ impl Bot {
 
     /// Send Private Msg
     pub async fn send_private_msg(&self, user_id: &str, msg: Vec<message::Message>) {
-        self.api_sender
-            .send(ApiChannelItem::Api(crate::api::Api::send_private_msg(
-                crate::api::SendPrivateMsg {
+        if self
+            .queue_api(
+                crate::api::Api::send_private_msg(crate::api::SendPrivateMsg {
                     user_id: user_id.to_string(),
                     message: msg.clone(),
                     auto_escape: false,
-                },
-            )))
+                }),
+                queue::Priority::Normal,
+            )
             .await
-            .unwrap();
+            .is_err()
+        {
+            event!(
+                Level::ERROR,
+                "Bot [{}] {}",
+                self.config.bot_id.red(),
+                ApiError::Disconnected
+            );
+            return;
+        }
         event!(
             Level::INFO,
             "Bot [{}] Send {:?} to Friend ({})",
@@ -84,6 +122,109 @@ impl Bot {
         );
     }
 
+    /// Send Group Msg，等待 Onebot 返回 message_id
+    pub async fn try_send_group_msg(
+        &self,
+        group_id: &str,
+        msg: Vec<message::Message>,
+    ) -> Result<api_resp::MessageId, ApiError> {
+        let resp = self
+            .check_api_resp(
+                crate::api::Api::send_group_msg(crate::api::SendGroupMsg {
+                    group_id: group_id.to_string(),
+                    message: msg,
+                    auto_escape: false,
+                }),
+                Some(queue::Priority::Normal),
+            )
+            .await?;
+        match resp.data {
+            RespData::MessageId(m) => Ok(m),
+            data => Err(ApiError::UnexpectedData(data)),
+        }
+    }
+
+    /// Send Private Msg，等待 Onebot 返回 message_id
+    pub async fn try_send_private_msg(
+        &self,
+        user_id: &str,
+        msg: Vec<message::Message>,
+    ) -> Result<api_resp::MessageId, ApiError> {
+        let resp = self
+            .check_api_resp(
+                crate::api::Api::send_private_msg(crate::api::SendPrivateMsg {
+                    user_id: user_id.to_string(),
+                    message: msg,
+                    auto_escape: false,
+                }),
+                Some(queue::Priority::Normal),
+            )
+            .await?;
+        match resp.data {
+            RespData::MessageId(m) => Ok(m),
+            data => Err(ApiError::UnexpectedData(data)),
+        }
+    }
+
+    /// 发送群合并转发，超出限制时拆分为多条依次发送
+    ///
+    /// 某条发送失败时停止发送，返回已发送的消息 ID 与错误
+    pub async fn send_group_forward(
+        &self,
+        group_id: &str,
+        forward: message::ForwardBuilder,
+    ) -> ForwardResult {
+        self.send_forward(forward, |messages| {
+            api::Api::send_group_forward_msg(api::SendGroupForwardMsg {
+                group_id: group_id.to_string(),
+                messages,
+            })
+        })
+        .await
+    }
+
+    /// 发送私聊合并转发，超出限制时拆分为多条依次发送
+    ///
+    /// 某条发送失败时停止发送，返回已发送的消息 ID 与错误
+    pub async fn send_private_forward(
+        &self,
+        user_id: &str,
+        forward: message::ForwardBuilder,
+    ) -> ForwardResult {
+        self.send_forward(forward, |messages| {
+            api::Api::send_private_forward_msg(api::SendPrivateForwardMsg {
+                user_id: user_id.to_string(),
+                messages,
+            })
+        })
+        .await
+    }
+
+    async fn send_forward<F>(
+        &self,
+        forward: message::ForwardBuilder,
+        build_api: F,
+    ) -> ForwardResult
+    where
+        F: Fn(Vec<message::Message>) -> api::Api,
+    {
+        let mut message_ids = vec![];
+        for messages in forward.build() {
+            let message_id = match self
+                .check_api_resp(build_api(messages), Some(queue::Priority::Normal))
+                .await
+            {
+                Ok(resp) => resp.parse_data(),
+                Err(e) => Err(e),
+            };
+            match message_id {
+                Ok(message_id) => message_ids.push(message_id),
+                Err(e) => return Err((message_ids, e)),
+            }
+        }
+        Ok(message_ids)
+    }
+
     /// 根据 MessageEvent 类型发送私聊消息或群消息
     pub async fn send_by_message_event(&self, event: &MessageEvent, msg: Vec<message::Message>) {
         match event {
@@ -92,44 +233,272 @@ impl Bot {
         }
     }
 
-    /// 请求 Onebot Api，不等待 Onebot 返回
-    pub async fn call_api(&self, api: api::Api) {
-        self.api_sender
-            .send(ApiChannelItem::Api(api.clone()))
+    /// Send Group Msg（字符串格式），auto_escape 为 false 时解析其中的 CQ 码
+    pub async fn send_group_raw_msg(&self, group_id: &str, msg: &str, auto_escape: bool) {
+        self.send_group_msg(group_id, message::from_string(msg, auto_escape))
+            .await
+    }
+
+    /// Send Private Msg（字符串格式），auto_escape 为 false 时解析其中的 CQ 码
+    pub async fn send_private_raw_msg(&self, user_id: &str, msg: &str, auto_escape: bool) {
+        self.send_private_msg(user_id, message::from_string(msg, auto_escape))
+            .await
+    }
+
+    /// 根据 MessageEvent 类型发送字符串格式消息，auto_escape 为 false 时解析其中的 CQ 码
+    pub async fn send_raw_by_message_event(
+        &self,
+        event: &MessageEvent,
+        msg: &str,
+        auto_escape: bool,
+    ) {
+        self.send_by_message_event(event, message::from_string(msg, auto_escape))
             .await
-            .unwrap();
+    }
+
+    /// 同意请求，remark 为好友备注，加群请求忽略
+    pub async fn approve(&self, event: &RequestEvent, remark: &str) -> Result<(), ApiError> {
+        self.handle_request(event, true, remark).await
+    }
+
+    /// 拒绝请求，reason 为拒绝理由，加好友请求忽略
+    pub async fn reject(&self, event: &RequestEvent, reason: &str) -> Result<(), ApiError> {
+        self.handle_request(event, false, reason).await
+    }
+
+    async fn handle_request(
+        &self,
+        event: &RequestEvent,
+        approve: bool,
+        text: &str,
+    ) -> Result<(), ApiError> {
+        match &event.request {
+            Request::FriendRequest(r) => {
+                self.try_set_friend_add_request(
+                    r.flag.clone(),
+                    approve,
+                    if approve { text } else { "" }.to_string(),
+                )
+                .await
+            }
+            Request::GroupRequest(r) => {
+                let sub_type = match r.sub_type {
+                    crate::event::request::GroupRequestType::Add => "add",
+                    crate::event::request::GroupRequestType::Invite => "invite",
+                };
+                self.try_set_group_add_request(
+                    r.flag.clone(),
+                    sub_type.to_string(),
+                    approve,
+                    if approve { "" } else { text }.to_string(),
+                )
+                .await
+            }
+            Request::Unknown(_) => Err(ApiError::UnknownRequest),
+        }
+    }
+
+    /// 经发送队列请求 Onebot Api，未启用限速时直接发送
+    pub async fn queue_api(
+        &self,
+        api: api::Api,
+        priority: queue::Priority,
+    ) -> Result<(), ApiError> {
+        self.enqueue(api, priority).await.map(|_| ())
+    }
+
+    /// 加入发送队列，返回发出通知，未启用限速时直接发送并返回 None
+    async fn enqueue(
+        &self,
+        api: api::Api,
+        priority: queue::Priority,
+    ) -> Result<Option<oneshot::Receiver<()>>, ApiError> {
+        if !self.send_queue.enabled() {
+            return self.send_api(api).await.map(|_| None);
+        }
         event!(
-            Level::INFO,
-            "Bot [{}] Calling Api {:?}",
+            Level::DEBUG,
+            "Bot [{}] Queue Api {:?} with {:?} priority",
             self.config.bot_id.red(),
-            api
+            api,
+            priority
         );
+        match self.send_queue.push(api, priority).await {
+            Some(sent) => Ok(Some(sent)),
+            None => Err(ApiError::Disconnected),
+        }
     }
 
-    /// 请求 Onebot Api，等待 Onebot 返回项（30s 后 timeout 返回 None）
-    pub async fn call_api_resp(&self, api: api::Api) -> Option<api_resp::ApiResp> {
-        let echo = api.get_echo();
-        self.api_sender
-            .send(ApiChannelItem::Api(api.clone()))
-            .await
-            .unwrap();
+    /// 发送队列状态
+    pub fn queue_stats(&self) -> queue::QueueStats {
+        self.send_queue.stats()
+    }
+
+    /// 请求 Onebot Api，不等待 Onebot 返回
+    pub async fn call_api(&self, api: api::Api) {
+        if let Err(e) = self.try_call_api(api).await {
+            event!(Level::ERROR, "Bot [{}] {}", self.config.bot_id.red(), e);
+        }
+    }
+
+    /// 请求 Onebot Api，不等待 Onebot 返回（Bot 已断开返回 Err）
+    ///
+    /// 不经过发送队列，需要限速时使用 `queue_api`
+    pub async fn try_call_api(&self, api: api::Api) -> Result<(), ApiError> {
+        self.send_api(api).await
+    }
+
+    /// 直接发送 Api
+    async fn send_api(&self, api: api::Api) -> Result<(), ApiError> {
         event!(
             Level::INFO,
             "Bot [{}] Calling Api {:?}",
             self.config.bot_id.red(),
             api
         );
-        let time = utils::timestamp();
-        let mut watcher = self.api_resp_watcher.clone();
-        while let Ok(_) = watcher.changed().await {
-            let resp = self.api_resp_watcher.borrow().clone();
-            if resp.echo == echo {
-                return Some(resp);
+        self.api_sender
+            .send(ApiChannelItem::Api(api))
+            .await
+            .map_err(|_| ApiError::Disconnected)
+    }
+
+    /// 请求 Onebot Api，等待 Onebot 返回项（30s 后 timeout 返回 None）
+    pub async fn call_api_resp(&self, api: api::Api) -> Option<api_resp::ApiResp> {
+        match self.wait_api_resp(api, None).await {
+            Ok(resp) => Some(resp),
+            Err(e) => {
+                event!(Level::WARN, "Bot [{}] {}", self.config.bot_id.red(), e);
+                None
             }
-            if utils::timestamp() > time + 30 {
-                return None;
+        }
+    }
+
+    /// 请求 Onebot Api，等待 Onebot 返回项（调用失败返回对应 ApiError）
+    ///
+    /// 不经过发送队列
+    pub async fn try_call_api_resp(&self, api: api::Api) -> Result<api_resp::ApiResp, ApiError> {
+        self.check_api_resp(api, None).await
+    }
+
+    /// 供各 Api 方法使用，消息 Api 以 Normal 优先级经发送队列发送
+    async fn call_typed_api_resp(&self, api: api::Api) -> Result<api_resp::ApiResp, ApiError> {
+        let priority = queue::is_message(&api).then_some(queue::Priority::Normal);
+        self.check_api_resp(api, priority).await
+    }
+
+    /// 请求未定义的 Onebot Api，返回原始 data 字段
+    pub async fn call_raw(
+        &self,
+        action: &str,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value, ApiError> {
+        self.try_call_api_resp(api::Api::custom(action, params))
+            .await
+            .map(|resp| resp.raw_data)
+    }
+
+    /// 请求未定义的 Onebot Api，按类型序列化参数并解析 data 字段
+    pub async fn call<P, R>(&self, action: &str, params: P) -> Result<R, ApiError>
+    where
+        P: serde::Serialize,
+        R: serde::de::DeserializeOwned,
+    {
+        let params =
+            serde_json::to_value(params).map_err(|e| ApiError::InvalidParams(e.to_string()))?;
+        self.try_call_api_resp(api::Api::custom(action, params))
+            .await?
+            .parse_data()
+    }
+
+    /// 等待 ApiResp，priority 不为 None 时经发送队列发送
+    async fn check_api_resp(
+        &self,
+        api: api::Api,
+        priority: Option<queue::Priority>,
+    ) -> Result<api_resp::ApiResp, ApiError> {
+        let resp = self.wait_api_resp(api, priority).await?;
+        if resp.is_ok() {
+            Ok(resp)
+        } else {
+            Err(resp.into())
+        }
+    }
+
+    /// 注册 echo 后发送 Api，等待对应 ApiResp
+    ///
+    /// 经发送队列发送时，超时自发出后开始计算
+    async fn wait_api_resp(
+        &self,
+        api: api::Api,
+        priority: Option<queue::Priority>,
+    ) -> Result<api_resp::ApiResp, ApiError> {
+        let echo = api.get_echo();
+        let (sender, receiver) = oneshot::channel();
+        self.api_resp_pending
+            .lock()
+            .unwrap()
+            .insert(echo.clone(), sender);
+        let sent = match priority {
+            Some(priority) => self.enqueue(api, priority).await,
+            None => self.send_api(api).await.map(|_| None),
+        };
+        let sent = match sent {
+            Ok(Some(sent)) => sent.await.map_err(|_| ApiError::Disconnected),
+            Ok(None) => Ok(()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = sent {
+            self.api_resp_pending.lock().unwrap().remove(&echo);
+            return Err(e);
+        }
+        match tokio::time::timeout(std::time::Duration::from_secs(30), receiver).await {
+            Ok(Ok(resp)) => resp,
+            Ok(Err(_)) => Err(ApiError::Disconnected),
+            Err(_) => {
+                self.api_resp_pending.lock().unwrap().remove(&echo);
+                Err(ApiError::Timeout)
             }
         }
-        None
     }
 }
+
+#[tokio::test]
+async fn call_api_bypass_queue_test() {
+    use std::time::Duration;
+
+    let (api_sender, mut api_receiver) = mpsc::channel(8);
+    let (action_sender, _) = mpsc::channel(1);
+    let mut config = config::BotConfig::default();
+    config.throttle.target_interval = 1000;
+    let bot = Bot::new(
+        "1".to_string(),
+        config,
+        api_sender,
+        action_sender,
+        ApiRespPending::default(),
+        std::sync::Arc::new(crate::storage::FileStorage::default()),
+    );
+    let send = || {
+        api::Api::send_group_msg(api::SendGroupMsg {
+            group_id: "10".to_string(),
+            message: vec![],
+            auto_escape: false,
+        })
+    };
+    async fn recv(receiver: &mut mpsc::Receiver<ApiChannelItem>) -> bool {
+        tokio::time::timeout(Duration::from_millis(200), receiver.recv())
+            .await
+            .ok()
+            .flatten()
+            .is_some()
+    }
+
+    // 同一群组的第二条消息需等待 target_interval
+    bot.send_group_msg("10", vec![]).await;
+    bot.send_group_msg("10", vec![]).await;
+    assert!(recv(&mut api_receiver).await);
+    // call_api 不经过发送队列，立即发送
+    bot.call_api(send()).await;
+    assert!(recv(&mut api_receiver).await);
+    assert_eq!(bot.queue_stats().depth, 1);
+}
@@ -0,0 +1,305 @@
+use crate::config::ThrottleConfig;
+use crate::{api, ApiChannelItem};
+use colored::*;
+use futures_util::FutureExt;
+use once_cell::sync::OnceCell;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::Instant;
+use tracing::{event, Level};
+
+/// 队列最多缓存的消息数，队列已满时入队等待
+pub const QUEUE_CAPACITY: usize = 1024;
+
+/// 发送优先级，高优先级消息先发送
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    High = 0,
+    Normal = 1,
+    Low = 2,
+}
+
+/// 发送队列状态
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueueStats {
+    /// 等待发送的消息总数
+    pub depth: usize,
+    /// 各优先级等待发送的消息数
+    pub high: usize,
+    pub normal: usize,
+    pub low: usize,
+    /// 已发送消息数
+    pub sent: u64,
+}
+
+#[derive(Debug)]
+struct Outgoing {
+    api: api::Api,
+    priority: Priority,
+    /// 发送目标，非消息 Api 为 None
+    target: Option<String>,
+    /// 发出后通知
+    sent: oneshot::Sender<()>,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    lanes: [AtomicUsize; 3],
+    sent: AtomicU64,
+}
+
+/// Bot 消息发送队列
+///
+/// 按 `ThrottleConfig` 限制发送速率与同一目标的发送间隔，首次入队时启动发送任务
+#[derive(Debug, Clone)]
+pub struct SendQueue {
+    config: Arc<RwLock<ThrottleConfig>>,
+    api_sender: mpsc::Sender<ApiChannelItem>,
+    sender: Arc<OnceCell<mpsc::Sender<Outgoing>>>,
+    counters: Arc<Counters>,
+}
+
+impl SendQueue {
+    pub fn new(config: ThrottleConfig, api_sender: mpsc::Sender<ApiChannelItem>) -> Self {
+        SendQueue {
+            config: Arc::new(RwLock::new(config)),
+            api_sender,
+            sender: Arc::new(OnceCell::new()),
+            counters: Arc::new(Counters::default()),
+        }
+    }
+
+    /// 是否启用限速
+    pub fn enabled(&self) -> bool {
+        self.config.read().unwrap().enabled()
+    }
+
+    /// 更新限速设置，对已入队的消息同样生效
+    pub fn set_config(&self, config: ThrottleConfig) {
+        *self.config.write().unwrap() = config;
+    }
+
+    /// 当前队列状态
+    pub fn stats(&self) -> QueueStats {
+        let lane = |p: Priority| self.counters.lanes[p as usize].load(Ordering::Relaxed);
+        let (high, normal, low) = (
+            lane(Priority::High),
+            lane(Priority::Normal),
+            lane(Priority::Low),
+        );
+        QueueStats {
+            depth: high + normal + low,
+            high,
+            normal,
+            low,
+            sent: self.counters.sent.load(Ordering::Relaxed),
+        }
+    }
+
+    /// 加入队列，队列已满时等待，发送任务已退出时返回 None
+    ///
+    /// 返回的 Receiver 在消息发出后完成
+    pub async fn push(&self, api: api::Api, priority: Priority) -> Option<oneshot::Receiver<()>> {
+        let target = target(&api);
+        let sender = self.sender.get_or_init(|| {
+            let (sender, receiver) = mpsc::channel(QUEUE_CAPACITY);
+            tokio::spawn(run(
+                self.config.clone(),
+                self.api_sender.clone(),
+                receiver,
+                self.counters.clone(),
+            ));
+            sender
+        });
+        let (sent, receiver) = oneshot::channel();
+        self.counters.lanes[priority as usize].fetch_add(1, Ordering::Relaxed);
+        let pushed = sender
+            .send(Outgoing {
+                api,
+                priority,
+                target,
+                sent,
+            })
+            .await
+            .is_ok();
+        if !pushed {
+            self.counters.lanes[priority as usize].fetch_sub(1, Ordering::Relaxed);
+            return None;
+        }
+        Some(receiver)
+    }
+}
+
+/// 是否为经发送队列发送的消息 Api
+pub fn is_message(api: &api::Api) -> bool {
+    matches!(
+        api,
+        api::Api::SendPrivateMsg { .. }
+            | api::Api::SendGroupMsg { .. }
+            | api::Api::SendMsg { .. }
+            | api::Api::SendPrivateForwardMsg { .. }
+            | api::Api::SendGroupForwardMsg { .. }
+    )
+}
+
+/// 消息 Api 的发送目标
+fn target(api: &api::Api) -> Option<String> {
+    match api {
+        api::Api::SendPrivateMsg { params, .. } => Some(format!("private:{}", params.user_id)),
+        api::Api::SendGroupMsg { params, .. } => Some(format!("group:{}", params.group_id)),
+        api::Api::SendPrivateForwardMsg { params, .. } => {
+            Some(format!("private:{}", params.user_id))
+        }
+        api::Api::SendGroupForwardMsg { params, .. } => Some(format!("group:{}", params.group_id)),
+        api::Api::SendMsg { params, .. } => match (&params.group_id, &params.user_id) {
+            (Some(group_id), _) => Some(format!("group:{}", group_id)),
+            (None, Some(user_id)) => Some(format!("private:{}", user_id)),
+            (None, None) => None,
+        },
+        _ => None,
+    }
+}
+
+/// 按优先级与发送间隔选出下一条可发送的消息，均未就绪时返回最早就绪时间
+fn next(
+    lanes: &[VecDeque<Outgoing>; 3],
+    ready: Instant,
+    last_sent: &HashMap<String, Instant>,
+    target_interval: Duration,
+    now: Instant,
+) -> Result<(usize, usize), Instant> {
+    let mut earliest: Option<Instant> = None;
+    for (lane_index, lane) in lanes.iter().enumerate() {
+        for (index, item) in lane.iter().enumerate() {
+            let item_ready = match item.target.as_ref().and_then(|t| last_sent.get(t)) {
+                Some(last) => ready.max(*last + target_interval),
+                None => ready,
+            };
+            if item_ready <= now {
+                return Ok((lane_index, index));
+            }
+            earliest = Some(earliest.map_or(item_ready, |e| e.min(item_ready)));
+        }
+    }
+    Err(earliest.unwrap_or(ready))
+}
+
+/// 已取出待发送的消息数
+fn depth(lanes: &[VecDeque<Outgoing>; 3]) -> usize {
+    lanes.iter().map(|lane| lane.len()).sum()
+}
+
+async fn run(
+    config: Arc<RwLock<ThrottleConfig>>,
+    api_sender: mpsc::Sender<ApiChannelItem>,
+    mut receiver: mpsc::Receiver<Outgoing>,
+    counters: Arc<Counters>,
+) {
+    let mut lanes: [VecDeque<Outgoing>; 3] = Default::default();
+    let mut last_sent: HashMap<String, Instant> = HashMap::new();
+    let mut ready = Instant::now();
+    let mut closed = false;
+    loop {
+        if lanes.iter().all(|lane| lane.is_empty()) {
+            if closed {
+                break;
+            }
+            match receiver.recv().await {
+                Some(item) => lanes[item.priority as usize].push_back(item),
+                None => break,
+            }
+        }
+        // 取出已到达的消息，保证按优先级选取，超出容量的消息留在 channel 中形成背压
+        while depth(&lanes) < QUEUE_CAPACITY {
+            match receiver.recv().now_or_never() {
+                Some(Some(item)) => lanes[item.priority as usize].push_back(item),
+                _ => break,
+            }
+        }
+
+        let (interval, target_interval) = {
+            let config = config.read().unwrap();
+            let interval = if config.rate > 0.0 {
+                Duration::from_secs_f64(1.0 / config.rate)
+            } else {
+                Duration::from_secs(0)
+            };
+            (interval, Duration::from_millis(config.target_interval))
+        };
+        let now = Instant::now();
+        let (lane, index) = match next(&lanes, ready, &last_sent, target_interval, now) {
+            Ok(found) => found,
+            Err(at) => {
+                tokio::select! {
+                    _ = tokio::time::sleep_until(at) => {}
+                    item = receiver.recv(), if !closed && depth(&lanes) < QUEUE_CAPACITY => match item {
+                        Some(item) => lanes[item.priority as usize].push_back(item),
+                        None => closed = true,
+                    },
+                }
+                continue;
+            }
+        };
+        let item = lanes[lane].remove(index).unwrap();
+        counters.lanes[lane].fetch_sub(1, Ordering::Relaxed);
+        if let Some(target) = item.target {
+            last_sent.insert(target, now);
+            last_sent.retain(|_, last| *last + target_interval > now);
+        }
+        ready = now + interval;
+        let Outgoing { api, sent, .. } = item;
+        if api_sender.send(ApiChannelItem::Api(api)).await.is_err() {
+            event!(
+                Level::WARN,
+                "{} Send queue closed with {} messages dropped",
+                "Bot".red(),
+                depth(&lanes)
+            );
+            for (lane, items) in lanes.iter().enumerate() {
+                counters.lanes[lane].fetch_sub(items.len(), Ordering::Relaxed);
+            }
+            break;
+        }
+        counters.sent.fetch_add(1, Ordering::Relaxed);
+        let _ = sent.send(());
+    }
+}
+
+#[tokio::test]
+async fn send_queue_test() {
+    let (api_sender, mut api_receiver) = mpsc::channel(8);
+    let queue = SendQueue::new(
+        ThrottleConfig {
+            rate: 0.0,
+            target_interval: 200,
+        },
+        api_sender,
+    );
+    let send = |group_id: &str| {
+        api::Api::send_group_msg(api::SendGroupMsg {
+            group_id: group_id.to_string(),
+            message: vec![],
+            auto_escape: false,
+        })
+    };
+    let start = Instant::now();
+    let first = queue.push(send("1"), Priority::Normal).await.unwrap();
+    assert!(queue.push(send("1"), Priority::Normal).await.is_some());
+    assert!(queue.push(send("2"), Priority::Low).await.is_some());
+
+    let mut targets = vec![];
+    for _ in 0..3 {
+        if let Some(ApiChannelItem::Api(api)) = api_receiver.recv().await {
+            targets.push(target(&api).unwrap());
+        }
+    }
+    // 同一群组的第二条消息需等待间隔，其他群组的消息先发送
+    assert_eq!(targets, vec!["group:1", "group:2", "group:1"]);
+    assert!(first.await.is_ok());
+    assert!(is_message(&send("1")));
+    assert!(start.elapsed() >= Duration::from_millis(200));
+    assert_eq!(queue.stats().depth, 0);
+}
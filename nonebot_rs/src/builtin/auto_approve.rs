@@ -0,0 +1,173 @@
+use crate::event::request::GroupRequestType;
+use crate::event::{Request, RequestEvent};
+use crate::matcher::prelude::*;
+use regex::Regex;
+use tracing::{event, Level};
+
+/// 自动同意策略
+#[derive(Debug, Clone)]
+pub enum ApprovePolicy {
+    /// 同意所有请求
+    All,
+    /// 仅同意 superuser 的加好友请求与群邀请
+    Superuser,
+    /// 验证信息匹配正则时同意，go-cqhttp 加群请求的验证信息包含问题与答案
+    Comment(Regex),
+}
+
+#[doc(hidden)]
+#[derive(Debug, Clone)]
+pub struct AutoApprove {
+    policy: ApprovePolicy,
+    /// 不满足策略时是否拒绝
+    reject: bool,
+    /// 拒绝理由
+    reason: String,
+}
+
+impl AutoApprove {
+    fn approves(&self, event: &RequestEvent, superusers: &[String]) -> bool {
+        let is_superuser = || match event.request.user_id() {
+            Some(user_id) => superusers.iter().any(|s| *s == user_id),
+            None => false,
+        };
+        match (&self.policy, &event.request) {
+            (_, Request::Unknown(_)) => false,
+            (ApprovePolicy::All, _) => true,
+            (ApprovePolicy::Superuser, Request::FriendRequest(_)) => is_superuser(),
+            (ApprovePolicy::Superuser, Request::GroupRequest(r)) => {
+                r.sub_type == GroupRequestType::Invite && is_superuser()
+            }
+            (ApprovePolicy::Comment(regex), Request::FriendRequest(r)) => {
+                regex.is_match(&r.comment)
+            }
+            (ApprovePolicy::Comment(regex), Request::GroupRequest(r)) => regex.is_match(&r.comment),
+        }
+    }
+}
+
+#[doc(hidden)]
+#[async_trait]
+impl Handler<RequestEvent> for AutoApprove {
+    fn match_(&self, event: &mut RequestEvent) -> bool {
+        !matches!(event.request, Request::Unknown(_))
+    }
+
+    async fn handle(&self, event: RequestEvent, matcher: Matcher<RequestEvent>) {
+        let superusers = match &matcher.bot {
+            Some(bot) => bot.config.superusers.clone(),
+            None => return,
+        };
+        let result = if self.approves(&event, &superusers) {
+            matcher.approve("").await
+        } else if self.reject {
+            matcher.reject(&self.reason).await
+        } else {
+            return;
+        };
+        if let Err(e) = result {
+            event!(
+                Level::WARN,
+                "Auto approve {:?} failed: {}",
+                event.request,
+                e
+            );
+        }
+    }
+
+    fn load_config(&mut self, config: std::collections::HashMap<String, toml::Value>) {
+        if let Some(policy) = config.get("policy").and_then(|p| p.as_str()) {
+            match parse_policy(policy, config.get("comment").and_then(|c| c.as_str())) {
+                Ok(policy) => self.policy = policy,
+                Err(e) => event!(
+                    Level::ERROR,
+                    "Invalid auto approve config: {}, keep policy {:?}",
+                    e,
+                    self.policy
+                ),
+            }
+        }
+        if let Some(reject) = config.get("reject").and_then(|r| r.as_bool()) {
+            self.reject = reject;
+        }
+        if let Some(reason) = config.get("reason").and_then(|r| r.as_str()) {
+            self.reason = reason.to_string();
+        }
+        event!(Level::DEBUG, "Load auto approve policy:{:?}", self.policy);
+    }
+}
+
+/// 解析配置中的策略，comment 为 policy 为 comment 时匹配验证信息的正则
+fn parse_policy(policy: &str, comment: Option<&str>) -> Result<ApprovePolicy, String> {
+    match policy {
+        "all" => Ok(ApprovePolicy::All),
+        "superuser" => Ok(ApprovePolicy::Superuser),
+        "comment" => {
+            let pattern = comment.ok_or("policy 为 comment 时需要设置 comment 正则")?;
+            Regex::new(pattern)
+                .map(ApprovePolicy::Comment)
+                .map_err(|e| format!("comment 不是有效的正则：{}", e))
+        }
+        policy => Err(format!("未知的自动同意策略 {}", policy)),
+    }
+}
+
+/// 自动处理加好友与加群请求的 Matcher
+///
+/// 可在 Nonebotrs.toml 中覆盖设置：
+///
+/// ```toml
+/// [matcher.autoapprove]
+/// policy = "comment"     # all|superuser|comment
+/// comment = "答案：42"   # policy 为 comment 时匹配验证信息的正则
+/// reject = true          # 不满足策略时拒绝，缺省忽略请求
+/// reason = "回答错误"    # 拒绝理由
+/// ```
+pub fn auto_approve(policy: ApprovePolicy) -> Matcher<RequestEvent> {
+    Matcher::new(
+        "AutoApprove",
+        AutoApprove {
+            policy,
+            reject: false,
+            reason: String::new(),
+        },
+    )
+}
+
+#[test]
+fn auto_approve_test() {
+    let request = |json: &str| -> RequestEvent { serde_json::from_str(json).unwrap() };
+    let invite = request(
+        r#"{"request_type":"group","sub_type":"invite","time":0,"self_id":1,"group_id":10,"user_id":2,"comment":"","flag":"f"}"#,
+    );
+    let friend = request(
+        r#"{"request_type":"friend","time":0,"self_id":1,"user_id":3,"comment":"答案：42","flag":"f"}"#,
+    );
+    let superusers = vec!["2".to_string()];
+    let handler = |policy| AutoApprove {
+        policy,
+        reject: false,
+        reason: String::new(),
+    };
+
+    let superuser = handler(ApprovePolicy::Superuser);
+    assert!(superuser.approves(&invite, &superusers));
+    assert!(!superuser.approves(&friend, &superusers));
+
+    let comment = handler(ApprovePolicy::Comment(Regex::new("答案：42$").unwrap()));
+    assert!(comment.approves(&friend, &superusers));
+    assert!(!comment.approves(&invite, &superusers));
+
+    // 配置错误时保留原策略
+    let mut superuser = superuser;
+    for config in &[
+        "policy = \"unknown\"",
+        "policy = \"comment\"",
+        "policy = \"comment\"\ncomment = \"(\"",
+    ] {
+        superuser.load_config(toml::from_str(config).unwrap());
+        assert!(matches!(superuser.policy, ApprovePolicy::Superuser));
+    }
+    superuser.load_config(toml::from_str("policy = \"all\"").unwrap());
+    assert!(matches!(superuser.policy, ApprovePolicy::All));
+}
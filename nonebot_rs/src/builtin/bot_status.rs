@@ -30,12 +30,20 @@ async fn build_status(event: &MessageEvent, matcher: &Matcher<MessageEvent>) ->
     } else {
         "-".to_string()
     };
+    let queue = match &matcher.bot {
+        Some(bot) => {
+            let stats = bot.queue_stats();
+            format!("{}（已发送 {}）", stats.depth, stats.sent)
+        }
+        None => "-".to_string(),
+    };
     format!(
-        "当前BotId：{}\n已连接时间：{}\n已加载好友数量：{}\n已加载群数量：{}",
+        "当前BotId：{}\n已连接时间：{}\n已加载好友数量：{}\n已加载群数量：{}\n待发送消息数量：{}",
         event.get_self_id(),
         time,
         friend_count,
-        group_count
+        group_count,
+        queue
     )
 }
 
@@ -21,7 +21,7 @@ macro_rules! on_command {
     ($event_type: ty, $command: expr) => {
         fn match_(&self, event: &mut $event_type) -> bool {
             if event.get_raw_message().starts_with($command) {
-                event.set_raw_message(event.get_raw_message().replace($command, "").to_string());
+                event.set_raw_message(event.get_raw_message()[$command.len()..].to_string());
                 true
             } else {
                 false
@@ -43,7 +43,7 @@ macro_rules! on_command {
             )*
             for command in commands.iter() {
                 if event.get_raw_message().starts_with(command) {
-                    event.set_raw_message(event.get_raw_message().replace(command, "").to_string());
+                    event.set_raw_message(event.get_raw_message()[command.len()..].to_string());
                     return true;
                 }
             }
@@ -52,6 +52,20 @@ macro_rules! on_command {
     };
 }
 
+/// 注册声明式命令匹配器
+///
+/// 为 `Matcher` 注册一个 `Command` 解析器，解析结果通过 `event.get_command_args()` 获取，
+/// 解析失败时将回复命令用法
+#[allow(unused_macros)]
+#[macro_export]
+macro_rules! on_command_args {
+    ($event_type: ty, $command: expr) => {
+        fn match_(&self, event: &mut $event_type) -> bool {
+            $command.match_event(event)
+        }
+    };
+}
+
 /// 注册字符匹配器
 ///
 /// 为 `Matcher` 注册一个字符匹配器，匹配字符将不会移除
@@ -84,6 +98,95 @@ macro_rules! on_start_with {
     };
 }
 
+/// 注册关键词匹配器
+///
+/// 消息纯文本包含任一关键词即匹配，图片等非文本消息段不参与匹配
+///
+/// 可在 `;` 后追加 `ignore_case`、`ignore_whitespace` 选项
+///
+/// ```ignore
+/// on_keyword!(MessageEvent, "早安", "morning"; ignore_case);
+/// ```
+#[allow(unused_macros)]
+#[macro_export]
+macro_rules! on_keyword {
+    ($event_type: ty, $($x:expr),+ $(; $($option:ident),+)?) => {
+        fn match_(&self, event: &mut $event_type) -> bool {
+            #[allow(unused_mut)]
+            let mut options = $crate::builtin::matches::MatchOptions::default();
+            $($(options.$option = true;)+)?
+            let text = event.get_message().extract_plain_text();
+            $crate::builtin::matches::keyword(&text, &[$($x),+], options)
+        }
+    };
+}
+
+/// 注册完全匹配器
+///
+/// 消息与任一字符串完全相同即匹配，选项同 `on_keyword!`
+#[allow(unused_macros)]
+#[macro_export]
+macro_rules! on_fullmatch {
+    ($event_type: ty, $($x:expr),+ $(; $($option:ident),+)?) => {
+        fn match_(&self, event: &mut $event_type) -> bool {
+            #[allow(unused_mut)]
+            let mut options = $crate::builtin::matches::MatchOptions::default();
+            $($(options.$option = true;)+)?
+            let text = event.get_message().extract_plain_text();
+            $crate::builtin::matches::fullmatch(&text, &[$($x),+], options)
+        }
+    };
+}
+
+/// 注册后缀匹配器
+///
+/// 消息以任一字符串结尾即匹配，选项同 `on_keyword!`
+#[allow(unused_macros)]
+#[macro_export]
+macro_rules! on_endswith {
+    ($event_type: ty, $($x:expr),+ $(; $($option:ident),+)?) => {
+        fn match_(&self, event: &mut $event_type) -> bool {
+            #[allow(unused_mut)]
+            let mut options = $crate::builtin::matches::MatchOptions::default();
+            $($(options.$option = true;)+)?
+            let text = event.get_message().extract_plain_text();
+            $crate::builtin::matches::endswith(&text, &[$($x),+], options)
+        }
+    };
+}
+
+/// 注册正则匹配器
+///
+/// 捕获组可在 `handle` 中通过 `matcher.get_captures()` 获取，选项同 `on_keyword!`
+///
+/// 正则在首次匹配时编译一次
+///
+/// ```ignore
+/// on_regex!(MessageEvent, r"^roll (?P<count>\d+)d(\d+)$"; ignore_case);
+/// ```
+#[allow(unused_macros)]
+#[macro_export]
+macro_rules! on_regex {
+    ($event_type: ty, $pattern: expr $(; $($option:ident),+)?) => {
+        fn match_(&self, event: &mut $event_type) -> bool {
+            fn options() -> $crate::builtin::matches::MatchOptions {
+                #[allow(unused_mut)]
+                let mut options = $crate::builtin::matches::MatchOptions::default();
+                $($(options.$option = true;)+)?
+                options
+            }
+            static REGEX: $crate::builtin::matches::LazyRegex =
+                $crate::builtin::matches::LazyRegex::new(|| {
+                    $crate::builtin::matches::compile($pattern, options())
+                });
+            match &*REGEX {
+                Some(regex) => $crate::builtin::matches::regex(event, regex, options()),
+                None => false,
+            }
+        }
+    };
+}
+
 #[doc(hidden)]
 #[allow(unused_macros)]
 #[macro_export]
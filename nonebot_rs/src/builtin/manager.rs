@@ -0,0 +1,163 @@
+use crate::matcher::acl::{MatcherSwitch, SwitchScope, SWITCH_NAMESPACE};
+use crate::matcher::prelude::*;
+
+static NAME: &str = "MatcherManager";
+
+#[doc(hidden)]
+#[derive(Clone)]
+pub struct MatcherManager {
+    commands: Vec<Command>,
+}
+
+impl Default for MatcherManager {
+    fn default() -> Self {
+        let switch = |name: &str| {
+            Command::new(name)
+                .arg("name", ArgType::String)
+                .option("group", Some('g'), ArgType::String)
+                .option("user", Some('u'), ArgType::String)
+                .help("不指定群组与用户时全局生效")
+        };
+        MatcherManager {
+            commands: vec![
+                Command::new("matcher list").help("列出已加载的 Matcher"),
+                switch("matcher enable"),
+                switch("matcher disable"),
+                Command::new("matcher rmtemp")
+                    .optional_arg("name", ArgType::String)
+                    .help("移除临时 Matcher，不指定 name 时移除全部"),
+            ],
+        }
+    }
+}
+
+impl MatcherManager {
+    async fn list(&self, matcher: &Matcher<MessageEvent>) -> String {
+        let mut infos = matcher.list_matchers().await;
+        infos.sort_by(|a, b| {
+            (a.event_type, a.priority, &a.name).cmp(&(b.event_type, b.priority, &b.name))
+        });
+        let mut lines = vec![format!("已加载 {} 个 Matcher", infos.len())];
+        for info in infos {
+            let mut line = format!(
+                "[{}] {} priority:{} block:{}",
+                info.event_type, info.name, info.priority, info.block
+            );
+            if info.temp {
+                line.push_str(" temp");
+            }
+            if let Some(timeout) = info.timeout {
+                line.push_str(&format!(" timeout:{}", timeout));
+            }
+            if info.disable {
+                line.push_str(" disabled");
+            }
+            let join = |ids: &std::collections::HashSet<String>| {
+                ids.iter().map(|s| s.as_str()).collect::<Vec<_>>().join(",")
+            };
+            if !info.acl.disabled_groups.is_empty() {
+                line.push_str(&format!(
+                    " disabled_groups:{}",
+                    join(&info.acl.disabled_groups)
+                ));
+            }
+            if !info.acl.disabled_users.is_empty() {
+                line.push_str(&format!(
+                    " disabled_users:{}",
+                    join(&info.acl.disabled_users)
+                ));
+            }
+            lines.push(line);
+        }
+        lines.join("\n")
+    }
+
+    async fn switch(
+        &self,
+        args: &CommandArgs,
+        matcher: &Matcher<MessageEvent>,
+        disable: bool,
+    ) -> String {
+        let name = args.get_str("name").unwrap_or_default();
+        if name == NAME {
+            return format!("不能修改 {} 自身", NAME);
+        }
+        if !matcher.list_matchers().await.iter().any(|i| i.name == name) {
+            return format!("未找到 Matcher {}", name);
+        }
+        let scope = match (args.get_str("group"), args.get_str("user")) {
+            (Some(_), Some(_)) => return "不能同时指定群组与用户".to_string(),
+            (Some(group), None) => SwitchScope::Group(group.to_string()),
+            (None, Some(user)) => SwitchScope::User(user.to_string()),
+            (None, None) => SwitchScope::Global,
+        };
+
+        // 持久化后由 Matchers 在 Bot 连接时恢复
+        if let Some(bot) = &matcher.bot {
+            let storage = bot.storage(SWITCH_NAMESPACE);
+            // 只保存运行时的修改，配置文件中的设置在恢复时叠加
+            let mut switch = match storage.get::<MatcherSwitch>(name).await {
+                Ok(switch) => switch.unwrap_or_default(),
+                Err(e) => return format!("读取 Matcher 开关失败：{}", e),
+            };
+            switch.set(&scope, disable);
+            if let Err(e) = storage.set(name, &switch).await {
+                return format!("保存 Matcher 开关失败：{}", e);
+            }
+        }
+        matcher
+            .set_matcher_disable(name, scope.clone(), disable)
+            .await;
+
+        let action = if disable { "禁用" } else { "启用" };
+        match scope {
+            SwitchScope::Global => format!("已{} {}", action, name),
+            SwitchScope::Group(group) => format!("已在群 {} {} {}", group, action, name),
+            SwitchScope::User(user) => format!("已对用户 {} {} {}", user, action, name),
+        }
+    }
+}
+
+#[doc(hidden)]
+#[async_trait]
+impl Handler<MessageEvent> for MatcherManager {
+    fn match_(&self, event: &mut MessageEvent) -> bool {
+        self.commands
+            .iter()
+            .any(|command| command.match_event(event))
+    }
+
+    async fn handle(&self, event: MessageEvent, matcher: Matcher<MessageEvent>) {
+        let args = match event.get_command_args() {
+            Some(args) => args,
+            None => return,
+        };
+        let reply = match args.command.as_str() {
+            "matcher list" => self.list(&matcher).await,
+            "matcher enable" => self.switch(args, &matcher, false).await,
+            "matcher disable" => self.switch(args, &matcher, true).await,
+            "matcher rmtemp" => {
+                let name = args.get_str("name");
+                matcher.remove_temp_matchers(name).await;
+                match name {
+                    Some(name) => format!("已移除临时 Matcher {}", name),
+                    None => "已移除所有临时 Matcher".to_string(),
+                }
+            }
+            _ => return,
+        };
+        matcher.send_text(&reply).await;
+    }
+}
+
+/// Matcher 管理 Matcher，仅 superuser 可用
+///
+/// - `matcher list` 列出已加载的 Matcher
+/// - `matcher enable|disable <name> [-g group] [-u user]` 启用或禁用 Matcher，重启后保留
+/// - `matcher rmtemp [name]` 移除临时 Matcher
+pub fn manager() -> Matcher<MessageEvent> {
+    Matcher::new(NAME, MatcherManager::default())
+        .add_pre_matcher(prematchers::to_me())
+        .add_pre_matcher(prematchers::command_start())
+        .add_rule(rules::is_superuser())
+}
@@ -0,0 +1,191 @@
+use crate::event::MessageEvent;
+use once_cell::sync::Lazy;
+use regex::{Regex, RegexBuilder};
+use std::collections::HashMap;
+
+/// 文本匹配选项
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MatchOptions {
+    /// 忽略大小写
+    pub ignore_case: bool,
+    /// 匹配前移除所有空白字符
+    ///
+    /// 正则匹配时只处理消息，正则中的空白需自行去除
+    pub ignore_whitespace: bool,
+}
+
+impl MatchOptions {
+    fn normalize(&self, text: &str) -> String {
+        let text: String = if self.ignore_whitespace {
+            text.chars().filter(|c| !c.is_whitespace()).collect()
+        } else {
+            text.to_string()
+        };
+        if self.ignore_case {
+            text.to_lowercase()
+        } else {
+            text
+        }
+    }
+}
+
+/// 正则匹配捕获组
+#[derive(Debug, Clone, Default)]
+pub struct Captures {
+    groups: Vec<Option<String>>,
+    named: HashMap<String, String>,
+}
+
+impl Captures {
+    fn new(regex: &Regex, captures: regex::Captures) -> Self {
+        let groups = captures
+            .iter()
+            .map(|m| m.map(|m| m.as_str().to_string()))
+            .collect();
+        let named = regex
+            .capture_names()
+            .flatten()
+            .filter_map(|name| {
+                let m = captures.name(name)?;
+                Some((name.to_string(), m.as_str().to_string()))
+            })
+            .collect();
+        Captures { groups, named }
+    }
+
+    /// 整个匹配的文本
+    pub fn matched(&self) -> &str {
+        self.get(0).unwrap_or_default()
+    }
+
+    /// 按序号获取捕获组，0 为整个匹配
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.groups.get(index)?.as_deref()
+    }
+
+    /// 按名称获取捕获组
+    pub fn name(&self, name: &str) -> Option<&str> {
+        self.named.get(name).map(|s| s.as_str())
+    }
+
+    /// 捕获组数量（包含整个匹配）
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// 是否无捕获组
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+}
+
+/// 首次使用时编译的正则，由 `on_regex!` 为每个匹配器生成
+pub type LazyRegex = Lazy<Option<Regex>>;
+
+/// 按选项编译正则，无效时记录错误并返回 None
+pub fn compile(pattern: &str, options: MatchOptions) -> Option<Regex> {
+    match RegexBuilder::new(pattern)
+        .case_insensitive(options.ignore_case)
+        .build()
+    {
+        Ok(regex) => Some(regex),
+        Err(e) => {
+            tracing::event!(tracing::Level::ERROR, "Invalid regex {}: {}", pattern, e);
+            None
+        }
+    }
+}
+
+/// 消息包含任一关键词
+pub fn keyword(text: &str, keywords: &[&str], options: MatchOptions) -> bool {
+    let text = options.normalize(text);
+    keywords
+        .iter()
+        .any(|k| text.contains(&options.normalize(k)))
+}
+
+/// 消息与任一字符串完全相同
+pub fn fullmatch(text: &str, patterns: &[&str], options: MatchOptions) -> bool {
+    let text = options.normalize(text);
+    patterns.iter().any(|p| text == options.normalize(p))
+}
+
+/// 消息以任一字符串结尾
+pub fn endswith(text: &str, suffixes: &[&str], options: MatchOptions) -> bool {
+    let text = options.normalize(text);
+    suffixes
+        .iter()
+        .any(|s| text.ends_with(&options.normalize(s)))
+}
+
+/// 正则匹配消息纯文本，匹配成功时将捕获组存入事件
+pub fn regex(event: &mut MessageEvent, regex: &Regex, options: MatchOptions) -> bool {
+    let text = MatchOptions {
+        ignore_case: false,
+        ..options
+    }
+    .normalize(&event.get_message().extract_plain_text());
+    let captures = match regex.captures(&text) {
+        Some(captures) => Captures::new(regex, captures),
+        None => return false,
+    };
+    event.set_captures(captures);
+    true
+}
+
+#[test]
+fn matches_test() {
+    let options = MatchOptions {
+        ignore_case: true,
+        ignore_whitespace: true,
+    };
+    assert!(keyword("Hello World", &["oWo"], options));
+    assert!(!keyword("Hello World", &["oWo"], MatchOptions::default()));
+    assert!(fullmatch(" Ping ", &["ping"], options));
+    assert!(endswith("good NIGHT", &["night"], options));
+
+    let regex = compile(r"roll (?P<count>\d+)d(\d+)", options).unwrap();
+    let captures = Captures::new(&regex, regex.captures("ROLL 2d6").unwrap());
+    assert_eq!(captures.matched(), "ROLL 2d6");
+    assert_eq!(captures.name("count"), Some("2"));
+    assert_eq!(captures.get(2), Some("6"));
+    assert_eq!(captures.get(3), None);
+}
+
+#[test]
+fn regex_macro_test() {
+    struct Roll;
+    impl Roll {
+        crate::on_regex!(MessageEvent, r"^roll (?P<count>\d+)d(\d+)$"; ignore_case);
+    }
+
+    let mut event: MessageEvent = serde_json::from_str(
+        r#"{"message_type":"private","time":0,"self_id":1,"sub_type":"friend","message_id":1,"user_id":2,"message":"ROLL 2d6","raw_message":"ROLL 2d6","font":0,"sender":{"user_id":2,"nickname":"","sex":"unknown","age":0}}"#,
+    )
+    .unwrap();
+    assert!(Roll.match_(&mut event));
+    let captures = event.get_captures().unwrap();
+    assert_eq!(captures.name("count"), Some("2"));
+    assert_eq!(captures.get(2), Some("6"));
+}
+
+#[test]
+fn plain_text_match_test() {
+    struct Image;
+    impl Image {
+        crate::on_keyword!(MessageEvent, "image");
+    }
+    struct Echo;
+    impl Echo {
+        crate::on_regex!(MessageEvent, r"^echo (.*)$");
+    }
+
+    let mut event: MessageEvent = serde_json::from_str(
+        r#"{"message_type":"private","time":0,"self_id":1,"sub_type":"friend","message_id":1,"user_id":2,"message":[{"type":"text","data":{"text":"echo hi"}},{"type":"image","data":{"file":"a.image"}}],"raw_message":"echo hi[CQ:image,file=a.image]","font":0,"sender":{"user_id":2,"nickname":"","sex":"unknown","age":0}}"#,
+    )
+    .unwrap();
+    // CQ 码不参与匹配
+    assert!(!Image.match_(&mut event));
+    assert!(Echo.match_(&mut event));
+    assert_eq!(event.get_captures().unwrap().get(1), Some("hi"));
+}
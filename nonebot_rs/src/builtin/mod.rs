@@ -1,3 +1,7 @@
+/// 自动处理加好友与加群请求
+#[cfg(feature = "matcher")]
+#[cfg_attr(docsrs, doc(cfg(feature = "matcher")))]
+pub mod auto_approve;
 /// Bot Status
 #[cfg(feature = "matcher")]
 #[cfg_attr(docsrs, doc(cfg(feature = "matcher")))]
@@ -8,6 +12,12 @@ pub mod bot_status;
 pub mod echo;
 #[doc(hidden)]
 pub mod macros;
+/// Matcher 管理
+#[cfg(feature = "matcher")]
+#[cfg_attr(docsrs, doc(cfg(feature = "matcher")))]
+pub mod manager;
+/// 关键词、正则等文本匹配
+pub mod matches;
 /// 内建 PreMatcher 函数
 #[cfg(feature = "matcher")]
 #[cfg_attr(docsrs, doc(cfg(feature = "matcher")))]
@@ -1,6 +1,8 @@
 use crate::config::BotConfig;
-use crate::event::MessageEvent;
+use crate::event::{MessageEvent, NoticeEvent, NoticeKind, RequestEvent, RequestKind};
 use crate::event::{SelfId, UserId};
+use crate::matcher::acl::AclEvent;
+use crate::matcher::rate_limit::RateLimit;
 use crate::matcher::Rule;
 use std::sync::Arc;
 
@@ -74,3 +76,121 @@ pub fn is_private_message_event() -> Rule<MessageEvent> {
     };
     Arc::new(is_private_message_event)
 }
+
+/// 判定 sender 在群内的角色是否为 role（owner|admin|member）
+fn has_group_role(event: &MessageEvent, role: &str) -> bool {
+    match event {
+        MessageEvent::Group(g) => g.sender.role == role,
+        MessageEvent::Private(_) => false,
+    }
+}
+
+/// 判定 sender 是否为群主
+pub fn is_group_owner() -> Rule<MessageEvent> {
+    Arc::new(|event: &MessageEvent, _: &BotConfig| has_group_role(event, "owner"))
+}
+
+/// 判定 sender 是否为群管理员（不含群主）
+pub fn is_group_admin() -> Rule<MessageEvent> {
+    Arc::new(|event: &MessageEvent, _: &BotConfig| has_group_role(event, "admin"))
+}
+
+/// 判定 sender 是否为普通群成员
+pub fn is_group_member() -> Rule<MessageEvent> {
+    Arc::new(|event: &MessageEvent, _: &BotConfig| has_group_role(event, "member"))
+}
+
+/// 判定 event 是否为好友私聊消息
+pub fn is_friend() -> Rule<MessageEvent> {
+    let is_friend = |event: &MessageEvent, _: &BotConfig| -> bool {
+        match event {
+            MessageEvent::Private(p) => p.sub_type == "friend",
+            MessageEvent::Group(_) => false,
+        }
+    };
+    Arc::new(is_friend)
+}
+
+/// 判定通知是否为指定类型
+///
+/// ```ignore
+/// Matcher::new("Welcome", Welcome {}).add_rule(rules::on_notice::<GroupIncrease>())
+/// ```
+pub fn on_notice<T>() -> Rule<NoticeEvent>
+where
+    T: NoticeKind,
+{
+    Arc::new(|event: &NoticeEvent, _: &BotConfig| event.get::<T>().is_some())
+}
+
+/// 判定请求是否为指定类型
+pub fn on_request<T>() -> Rule<RequestEvent>
+where
+    T: RequestKind,
+{
+    Arc::new(|event: &RequestEvent, _: &BotConfig| event.get::<T>().is_some())
+}
+
+/// 同时满足两个 rule
+///
+/// ```ignore
+/// // superuser 或指定群的群主、管理员
+/// rules::or(
+///     rules::is_superuser(),
+///     rules::and(
+///         rules::in_group("123456".to_string()),
+///         rules::or(rules::is_group_owner(), rules::is_group_admin()),
+///     ),
+/// )
+/// ```
+pub fn and<E>(a: Rule<E>, b: Rule<E>) -> Rule<E>
+where
+    E: 'static,
+{
+    Arc::new(move |event: &E, config: &BotConfig| a(event, config) && b(event, config))
+}
+
+/// 满足任一 rule
+pub fn or<E>(a: Rule<E>, b: Rule<E>) -> Rule<E>
+where
+    E: 'static,
+{
+    Arc::new(move |event: &E, config: &BotConfig| a(event, config) || b(event, config))
+}
+
+/// 不满足 rule
+pub fn not<E>(rule: Rule<E>) -> Rule<E>
+where
+    E: 'static,
+{
+    Arc::new(move |event: &E, config: &BotConfig| !rule(event, config))
+}
+
+/// 限流，超过限制时不匹配
+///
+/// 在 Handler 匹配前计数，需要冷却回复或仅对匹配成功的事件计数时
+/// 使用 `Matcher::set_rate_limit`
+pub fn rate_limit<E>(rate_limit: RateLimit) -> Rule<E>
+where
+    E: AclEvent,
+{
+    Arc::new(move |event: &E, _: &BotConfig| rate_limit.check(event).is_ok())
+}
+
+#[test]
+fn rule_combinator_test() {
+    let event: MessageEvent = serde_json::from_str(
+        r#"{"message_type":"group","time":0,"self_id":1,"sub_type":"normal","message_id":1,"group_id":10,"user_id":2,"anonymous":null,"message":"hi","raw_message":"hi","font":0,"sender":{"user_id":2,"nickname":"","card":"","sex":"unknown","age":0,"area":"","level":"","role":"admin","title":""}}"#,
+    )
+    .unwrap();
+    let config = BotConfig::default();
+    let rule = or(
+        is_superuser(),
+        and(in_group("10".to_string()), is_group_admin()),
+    );
+    assert!(rule(&event, &config));
+    assert!(!and(rule.clone(), not(in_group("10".to_string())))(
+        &event, &config
+    ));
+    assert!(!or(is_group_owner(), is_friend())(&event, &config));
+}
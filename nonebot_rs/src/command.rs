@@ -0,0 +1,502 @@
+//! `Command` 声明命令名、别名与参数，按消息段解析为 `CommandArgs`，
+//! 解析失败时返回带有自动生成用法文本的 `CommandError`。
+
+use crate::event::{MessageEvent, MetaEvent, NoticeEvent, RequestEvent};
+use crate::message::Message;
+use std::collections::{HashMap, HashSet};
+
+/// 参数类型
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArgType {
+    /// 整数
+    Int,
+    /// 单个词
+    String,
+    /// @某人
+    At,
+    /// 图片
+    Image,
+}
+
+impl ArgType {
+    fn name(&self) -> &'static str {
+        match self {
+            ArgType::Int => "整数",
+            ArgType::String => "文本",
+            ArgType::At => "@用户",
+            ArgType::Image => "图片",
+        }
+    }
+
+    fn parse(&self, token: &Token) -> Option<ArgValue> {
+        match (self, token) {
+            (ArgType::Int, Token::Word(w)) => w.parse().ok().map(ArgValue::Int),
+            (ArgType::String, Token::Word(w)) => Some(ArgValue::String(w.clone())),
+            (ArgType::At, Token::At(qq)) => Some(ArgValue::At(qq.clone())),
+            (ArgType::Image, Token::Image(file)) => Some(ArgValue::Image(file.clone())),
+            _ => None,
+        }
+    }
+}
+
+/// 解析后的参数值
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArgValue {
+    Int(i64),
+    String(String),
+    /// 被 @ 的 ID
+    At(String),
+    /// 图片 URL，不存在时为文件名
+    Image(String),
+}
+
+#[derive(Debug, Clone)]
+struct ArgSpec {
+    name: String,
+    type_: ArgType,
+    required: bool,
+}
+
+#[derive(Debug, Clone)]
+struct FlagSpec {
+    name: String,
+    short: Option<char>,
+    /// None 为开关，Some 为带值选项
+    value: Option<ArgType>,
+}
+
+/// 命令声明
+///
+/// ```rust
+/// use nonebot_rs::command::{ArgType, Command};
+///
+/// let command = Command::new("ban")
+///     .alias("禁言")
+///     .arg("user", ArgType::At)
+///     .optional_arg("minutes", ArgType::Int)
+///     .flag("silent", Some('s'));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Command {
+    name: String,
+    aliases: Vec<String>,
+    args: Vec<ArgSpec>,
+    rest: Option<String>,
+    flags: Vec<FlagSpec>,
+    help: Option<String>,
+}
+
+/// 命令解析结果
+#[derive(Debug, Clone, Default)]
+pub struct CommandArgs {
+    /// 匹配到的命令名或别名
+    pub command: String,
+    values: HashMap<String, ArgValue>,
+    flags: HashSet<String>,
+}
+
+/// 命令解析错误
+#[derive(Debug, Clone)]
+pub struct CommandError {
+    /// 错误原因
+    pub reason: String,
+    /// 命令用法
+    pub usage: String,
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}\n{}", self.reason, self.usage)
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+/// 消息段拆分后的词元
+#[derive(Debug, Clone)]
+enum Token {
+    Word(String),
+    At(String),
+    Image(String),
+    Other,
+}
+
+fn tokenize(message: &[Message]) -> Vec<Token> {
+    let mut tokens = vec![];
+    for segment in message {
+        match segment {
+            Message::Text { text } => tokens.extend(
+                text.split_whitespace()
+                    .map(|word| Token::Word(word.to_string())),
+            ),
+            Message::At { qq } => tokens.push(Token::At(qq.clone())),
+            Message::Image { file, url, .. } => {
+                tokens.push(Token::Image(url.clone().unwrap_or_else(|| file.clone())))
+            }
+            // 回复不参与命令解析
+            Message::Reply { .. } => {}
+            _ => tokens.push(Token::Other),
+        }
+    }
+    tokens
+}
+
+impl Command {
+    /// 新建命令，多个词的命令名以空格分隔
+    pub fn new(name: &str) -> Self {
+        Command {
+            name: name.to_string(),
+            aliases: vec![],
+            args: vec![],
+            rest: None,
+            flags: vec![],
+            help: None,
+        }
+    }
+
+    /// 添加别名
+    pub fn alias(mut self, alias: &str) -> Self {
+        self.aliases.push(alias.to_string());
+        self
+    }
+
+    /// 添加必填位置参数
+    pub fn arg(mut self, name: &str, type_: ArgType) -> Self {
+        self.args.push(ArgSpec {
+            name: name.to_string(),
+            type_,
+            required: true,
+        });
+        self
+    }
+
+    /// 添加可选位置参数，需位于必填参数之后
+    pub fn optional_arg(mut self, name: &str, type_: ArgType) -> Self {
+        self.args.push(ArgSpec {
+            name: name.to_string(),
+            type_,
+            required: false,
+        });
+        self
+    }
+
+    /// 剩余的文本合并为一个 `String` 参数
+    pub fn rest(mut self, name: &str) -> Self {
+        self.rest = Some(name.to_string());
+        self
+    }
+
+    /// 添加开关 `--name` / `-s`
+    pub fn flag(mut self, name: &str, short: Option<char>) -> Self {
+        self.flags.push(FlagSpec {
+            name: name.to_string(),
+            short,
+            value: None,
+        });
+        self
+    }
+
+    /// 添加带值选项 `--name value` / `-s value`
+    pub fn option(mut self, name: &str, short: Option<char>, type_: ArgType) -> Self {
+        self.flags.push(FlagSpec {
+            name: name.to_string(),
+            short,
+            value: Some(type_),
+        });
+        self
+    }
+
+    /// 设置说明，附加在用法文本之后
+    pub fn help(mut self, help: &str) -> Self {
+        self.help = Some(help.to_string());
+        self
+    }
+
+    /// 生成用法文本
+    pub fn usage(&self) -> String {
+        let mut usage = format!("用法：{}", self.name);
+        for arg in &self.args {
+            if arg.required {
+                usage.push_str(&format!(" <{}:{}>", arg.name, arg.type_.name()));
+            } else {
+                usage.push_str(&format!(" [{}:{}]", arg.name, arg.type_.name()));
+            }
+        }
+        if let Some(rest) = &self.rest {
+            usage.push_str(&format!(" [{}...]", rest));
+        }
+        for flag in &self.flags {
+            let name = match flag.short {
+                Some(short) => format!("-{}|--{}", short, flag.name),
+                None => format!("--{}", flag.name),
+            };
+            match flag.value {
+                Some(type_) => usage.push_str(&format!(" [{} <{}>]", name, type_.name())),
+                None => usage.push_str(&format!(" [{}]", name)),
+            }
+        }
+        if !self.aliases.is_empty() {
+            usage.push_str(&format!("\n别名：{}", self.aliases.join("、")));
+        }
+        if let Some(help) = &self.help {
+            usage.push('\n');
+            usage.push_str(help);
+        }
+        usage
+    }
+
+    fn error(&self, reason: String) -> CommandError {
+        CommandError {
+            reason,
+            usage: self.usage(),
+        }
+    }
+
+    /// 匹配命令名，返回命令名与其占用的词元数
+    fn match_name(&self, tokens: &[Token]) -> Option<(String, usize)> {
+        std::iter::once(&self.name)
+            .chain(self.aliases.iter())
+            .find_map(|name| {
+                let words: Vec<&str> = name.split_whitespace().collect();
+                let matched = words.len() <= tokens.len()
+                    && words.iter().zip(tokens).all(|(word, token)| match token {
+                        Token::Word(w) => w == word,
+                        _ => false,
+                    });
+                if matched {
+                    Some((name.clone(), words.len()))
+                } else {
+                    None
+                }
+            })
+    }
+
+    fn find_flag(&self, word: &str) -> Option<&FlagSpec> {
+        if let Some(long) = word.strip_prefix("--") {
+            return self.flags.iter().find(|f| f.name == long);
+        }
+        let mut chars = word.strip_prefix('-')?.chars();
+        match (chars.next(), chars.next()) {
+            (Some(short), None) => self.flags.iter().find(|f| f.short == Some(short)),
+            _ => None,
+        }
+    }
+
+    /// 解析消息段，命令名不匹配时返回 None
+    pub fn parse(&self, message: &[Message]) -> Option<Result<CommandArgs, CommandError>> {
+        let tokens = tokenize(message);
+        let (command, skip) = self.match_name(&tokens)?;
+        Some(self.parse_args(command, &tokens[skip..]))
+    }
+
+    fn parse_args(&self, command: String, tokens: &[Token]) -> Result<CommandArgs, CommandError> {
+        let mut args = CommandArgs {
+            command,
+            ..Default::default()
+        };
+        let mut positional = self.args.iter();
+        let mut rest: Vec<&str> = vec![];
+        let mut tokens = tokens.iter();
+        while let Some(token) = tokens.next() {
+            if let Token::Word(word) = token {
+                // 负数不视为开关
+                if word.starts_with('-') && word.parse::<i64>().is_err() {
+                    let flag = self
+                        .find_flag(word)
+                        .ok_or_else(|| self.error(format!("未知选项 {}", word)))?;
+                    match flag.value {
+                        Some(type_) => {
+                            let value =
+                                tokens.next().and_then(|t| type_.parse(t)).ok_or_else(|| {
+                                    self.error(format!("选项 --{} 需要{}", flag.name, type_.name()))
+                                })?;
+                            args.values.insert(flag.name.clone(), value);
+                        }
+                        None => {
+                            args.flags.insert(flag.name.clone());
+                        }
+                    }
+                    continue;
+                }
+            }
+            match positional.next() {
+                Some(spec) => {
+                    let value = spec.type_.parse(token).ok_or_else(|| {
+                        self.error(format!("参数 {} 需要{}", spec.name, spec.type_.name()))
+                    })?;
+                    args.values.insert(spec.name.clone(), value);
+                }
+                None => match (&self.rest, token) {
+                    (Some(_), Token::Word(word)) => rest.push(word),
+                    _ => return Err(self.error("参数过多".to_string())),
+                },
+            }
+        }
+        if let Some(spec) = positional.find(|spec| spec.required) {
+            return Err(self.error(format!("缺少参数 {}", spec.name)));
+        }
+        if let Some(name) = &self.rest {
+            args.values
+                .insert(name.clone(), ArgValue::String(rest.join(" ")));
+        }
+        Ok(args)
+    }
+
+    /// 解析 `MessageEvent`，匹配时将解析结果存入事件并返回 true
+    ///
+    /// 按消息段解析，消息段为空时回退为解析 raw_message 中的 CQ 码
+    ///
+    /// 在 `Handler::match_` 中调用，解析失败时 Matcher 将回复用法文本
+    pub fn match_event(&self, event: &mut MessageEvent) -> bool {
+        let message = event.get_message();
+        let result = if message.is_empty() {
+            self.parse(&crate::message::from_cq_string(event.get_raw_message()))
+        } else {
+            self.parse(message)
+        };
+        match result {
+            Some(result) => {
+                event.set_command_args(result);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl CommandArgs {
+    /// 获取参数
+    pub fn get(&self, name: &str) -> Option<&ArgValue> {
+        self.values.get(name)
+    }
+
+    /// 获取整数参数
+    pub fn get_i64(&self, name: &str) -> Option<i64> {
+        match self.values.get(name) {
+            Some(ArgValue::Int(i)) => Some(*i),
+            _ => None,
+        }
+    }
+
+    /// 获取文本参数
+    pub fn get_str(&self, name: &str) -> Option<&str> {
+        match self.values.get(name) {
+            Some(ArgValue::String(s)) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// 获取 @ 参数
+    pub fn get_at(&self, name: &str) -> Option<&str> {
+        match self.values.get(name) {
+            Some(ArgValue::At(qq)) => Some(qq),
+            _ => None,
+        }
+    }
+
+    /// 获取图片参数
+    pub fn get_image(&self, name: &str) -> Option<&str> {
+        match self.values.get(name) {
+            Some(ArgValue::Image(image)) => Some(image),
+            _ => None,
+        }
+    }
+
+    /// 开关是否启用
+    pub fn has_flag(&self, name: &str) -> bool {
+        self.flags.contains(name)
+    }
+}
+
+/// 为 Matcher 提供回复命令用法等提示的 Api
+pub trait CommandEvent {
+    /// 取出命令解析错误，返回回复用法的 Api
+    fn take_command_error(&mut self) -> Option<crate::api::Api> {
+        None
+    }
+
+    /// 构造回复事件来源的 Api，无法回复时返回 None
+    fn reply_api(&self, _message: Vec<Message>) -> Option<crate::api::Api> {
+        None
+    }
+}
+
+impl CommandEvent for MessageEvent {
+    fn take_command_error(&mut self) -> Option<crate::api::Api> {
+        let error = match self.command_args_mut().as_deref() {
+            Some(Err(error)) => error.to_string(),
+            _ => return None,
+        };
+        *self.command_args_mut() = None;
+        self.reply_api(vec![Message::text(error)])
+    }
+
+    fn reply_api(&self, message: Vec<Message>) -> Option<crate::api::Api> {
+        Some(match self {
+            MessageEvent::Private(p) => {
+                crate::api::Api::send_private_msg(crate::api::SendPrivateMsg {
+                    user_id: p.user_id.clone(),
+                    message,
+                    auto_escape: false,
+                })
+            }
+            MessageEvent::Group(g) => crate::api::Api::send_group_msg(crate::api::SendGroupMsg {
+                group_id: g.group_id.clone(),
+                message,
+                auto_escape: false,
+            }),
+        })
+    }
+}
+
+impl CommandEvent for NoticeEvent {}
+
+impl CommandEvent for RequestEvent {}
+
+impl CommandEvent for MetaEvent {}
+
+#[test]
+fn command_parse_test() {
+    let command = Command::new("ban")
+        .alias("禁言")
+        .arg("user", ArgType::At)
+        .optional_arg("minutes", ArgType::Int)
+        .option("reason", Some('r'), ArgType::String)
+        .flag("silent", Some('s'));
+
+    let message = crate::message::from_cq_string("禁言 [CQ:at,qq=123] 10 -s --reason spam");
+    let args = command.parse(&message).unwrap().unwrap();
+    assert_eq!(args.command, "禁言");
+    assert_eq!(args.get_at("user"), Some("123"));
+    assert_eq!(args.get_i64("minutes"), Some(10));
+    assert_eq!(args.get_str("reason"), Some("spam"));
+    assert!(args.has_flag("silent"));
+
+    assert!(command
+        .parse(&crate::message::from_cq_string("banana"))
+        .is_none());
+    let error = command
+        .parse(&crate::message::from_cq_string("ban abc"))
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(error.reason, "参数 user 需要@用户");
+    assert!(error
+        .usage
+        .starts_with("用法：ban <user:@用户> [minutes:整数]"));
+
+    let echo = Command::new("echo").rest("text");
+    let args = echo
+        .parse(&crate::message::from_cq_string("echo echo  hello"))
+        .unwrap()
+        .unwrap();
+    assert_eq!(args.get_str("text"), Some("echo hello"));
+
+    // raw_message 不含 CQ 码时按消息段解析
+    let mut event: MessageEvent = serde_json::from_str(
+        r#"{"message_type":"private","time":0,"self_id":1,"sub_type":"friend","message_id":1,"user_id":2,"message":[{"type":"text","data":{"text":"ban "}},{"type":"at","data":{"qq":"123"}}],"raw_message":"ban @123","font":0,"sender":{"user_id":2,"nickname":"","sex":"unknown","age":0}}"#,
+    )
+    .unwrap();
+    assert!(command.match_event(&mut event));
+    let args = event.get_command_args().unwrap();
+    assert_eq!(args.get_at("user"), Some("123"));
+}
@@ -0,0 +1,76 @@
+use crate::event::Event;
+use std::sync::{Arc, Mutex};
+use tokio::time::{Duration, Instant};
+
+/// 心跳看门狗，记录连接最后一次心跳
+///
+/// 超过 `missed` 个心跳间隔未收到心跳即视为连接失效，`missed` 为 0 时不检测
+#[derive(Debug, Clone)]
+pub struct HeartbeatWatchdog {
+    missed: u32,
+    /// (最后一次心跳时间, 心跳间隔)
+    last: Arc<Mutex<Option<(Instant, Duration)>>>,
+}
+
+impl HeartbeatWatchdog {
+    pub fn new(missed: u32) -> Self {
+        HeartbeatWatchdog {
+            missed,
+            last: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// 收到上报时调用，记录心跳事件
+    pub fn feed(&self, event: &Event) {
+        if let Event::Meta(meta) = event {
+            if meta.meta_event_type != "heartbeat" {
+                return;
+            }
+            if let Some(interval) = meta.interval.filter(|i| *i > 0) {
+                let interval = Duration::from_millis(interval as u64);
+                *self.last.lock().unwrap() = Some((Instant::now(), interval));
+            }
+        }
+    }
+
+    /// 是否为同一连接的看门狗
+    pub fn same(&self, other: &HeartbeatWatchdog) -> bool {
+        Arc::ptr_eq(&self.last, &other.last)
+    }
+
+    /// 心跳超时截止时间，尚未收到心跳时为 None
+    fn deadline(&self) -> Option<Instant> {
+        self.last
+            .lock()
+            .unwrap()
+            .map(|(last, interval)| last + interval * self.missed)
+    }
+
+    /// 等待至心跳超时，未启用或未收到过心跳时不会返回
+    pub async fn stale(&self) {
+        if self.missed == 0 {
+            return std::future::pending().await;
+        }
+        loop {
+            match self.deadline() {
+                Some(deadline) if deadline <= Instant::now() => return,
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => tokio::time::sleep(Duration::from_secs(5)).await,
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn heartbeat_stale_test() {
+    let watchdog = HeartbeatWatchdog::new(3);
+    let heartbeat: Event = serde_json::from_str(
+        r#"{"post_type":"meta_event","meta_event_type":"heartbeat","self_id":1,"time":0,"interval":10}"#,
+    )
+    .unwrap();
+
+    let start = Instant::now();
+    watchdog.feed(&heartbeat);
+    watchdog.stale().await;
+    assert!(start.elapsed() >= Duration::from_millis(30));
+}
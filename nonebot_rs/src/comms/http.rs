@@ -0,0 +1,350 @@
+use super::heartbeat::HeartbeatWatchdog;
+use super::utils::{send_event, Codec};
+use crate::config::Protocols;
+use crate::event::RecvItem;
+use crate::{ActionSender, ApiChannelItem, EventSender, ShutdownReceiver};
+use colored::*;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Client, Method, Request, Response, Server, StatusCode};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tracing::{event, Level};
+
+/// HTTP POST Server 共享状态
+#[derive(Clone)]
+struct HttpServer {
+    event_sender: EventSender,
+    action_sender: ActionSender,
+    access_token: crate::config::AccessToken,
+    secret: crate::config::Secret,
+    /// bot_id -> Onebot HTTP API 地址
+    http_apis: HashMap<String, String>,
+    /// 已添加至 Nonebot 的 Bot 及其心跳看门狗、编解码器
+    bots: Arc<Mutex<HashMap<String, (HeartbeatWatchdog, Codec)>>>,
+    protocols: Protocols,
+    heartbeat_missed: u32,
+}
+
+/// start HTTP POST Server
+#[allow(clippy::too_many_arguments)]
+pub async fn run(
+    host: std::net::Ipv4Addr,
+    port: u16,
+    event_sender: EventSender,
+    action_sender: ActionSender,
+    access_token: crate::config::AccessToken,
+    secret: crate::config::Secret,
+    http_apis: HashMap<String, String>,
+    protocols: Protocols,
+    heartbeat_missed: u32,
+    mut shutdown: ShutdownReceiver,
+) {
+    let server = HttpServer {
+        event_sender,
+        action_sender,
+        access_token,
+        secret,
+        http_apis,
+        bots: Arc::new(Mutex::new(HashMap::new())),
+        protocols,
+        heartbeat_missed,
+    };
+
+    let make_svc = make_service_fn(move |_| {
+        let server = server.clone();
+        async move { Ok::<_, Infallible>(service_fn(move |req| handle_post(req, server.clone()))) }
+    });
+
+    let addr = std::net::SocketAddr::from(std::net::SocketAddrV4::new(host, port));
+    event!(Level::INFO, "Serveing at -> http://{}:{}", host, port);
+    let server = Server::bind(&addr)
+        .serve(make_svc)
+        .with_graceful_shutdown(async move {
+            shutdown.changed().await.ok();
+        });
+    if let Err(e) = server.await {
+        event!(Level::ERROR, "HTTP server error {}", e);
+    }
+}
+
+fn empty_response(status: StatusCode) -> Response<Body> {
+    let mut resp = Response::new(Body::empty());
+    *resp.status_mut() = status;
+    resp
+}
+
+/// handle a income HTTP POST
+async fn handle_post(req: Request<Body>, server: HttpServer) -> Result<Response<Body>, Infallible> {
+    if req.method() != Method::POST {
+        return Ok(empty_response(StatusCode::METHOD_NOT_ALLOWED));
+    }
+
+    // get bot_id && signature from headers
+    let headers = req.headers();
+    let bot_id = match headers.get("X-Self-ID").and_then(|id| id.to_str().ok()) {
+        Some(bot_id) => bot_id.to_owned(),
+        None => return Ok(empty_response(StatusCode::BAD_REQUEST)),
+    };
+    let signature: Option<String> = headers
+        .get("X-Signature")
+        .and_then(|sig| sig.to_str().ok())
+        .map(|sig| sig.to_owned());
+
+    let body = match hyper::body::to_bytes(req.into_body()).await {
+        Ok(body) => body,
+        Err(e) => {
+            event!(Level::WARN, "Read HTTP body error {}", e);
+            return Ok(empty_response(StatusCode::BAD_REQUEST));
+        }
+    };
+
+    if !server.secret.check_signature(&bot_id, signature, &body) {
+        return Ok(empty_response(StatusCode::FORBIDDEN));
+    }
+
+    let ((watchdog, codec), need_add) = {
+        let mut bots = server.bots.lock().unwrap();
+        match bots.get(&bot_id) {
+            Some(bot) => (bot.clone(), false),
+            None => {
+                let bot = (
+                    HeartbeatWatchdog::new(server.heartbeat_missed),
+                    Codec::new(server.protocols.get(&bot_id)),
+                );
+                bots.insert(bot_id.clone(), bot.clone());
+                (bot, true)
+            }
+        }
+    };
+    if need_add {
+        add_bot(&server, &bot_id, watchdog.clone(), codec.clone()).await;
+    }
+
+    match codec.decode(&body) {
+        Some(RecvItem::Event(event)) => {
+            watchdog.feed(&event);
+            send_event(&server.event_sender, event).await
+        }
+        _ => return Ok(empty_response(StatusCode::BAD_REQUEST)),
+    }
+
+    Ok(empty_response(StatusCode::NO_CONTENT))
+}
+
+/// 首次收到 Bot 上报时添加 Bot
+async fn add_bot(server: &HttpServer, bot_id: &str, watchdog: HeartbeatWatchdog, codec: Codec) {
+    let http_api = server.http_apis.get(bot_id).cloned();
+    event!(
+        Level::INFO,
+        "HTTP Client {} is connectted. The api address is {:?}",
+        bot_id.red(),
+        http_api
+    );
+
+    // build channel
+    let (sender, receiver) = mpsc::channel(32);
+    let api_resp_pending = crate::ApiRespPending::default();
+
+    // add bot to Nonebot
+    server
+        .action_sender
+        .send(crate::Action::AddBot {
+            bot_id: bot_id.to_string(),
+            api_sender: sender,
+            action_sender: server.action_sender.clone(),
+            api_resp_pending: api_resp_pending.clone(),
+        })
+        .await
+        .ok();
+
+    tokio::spawn(handler_http_api(
+        http_api,
+        server.access_token.get(bot_id).to_string(),
+        codec,
+        api_resp_pending.clone(),
+        receiver,
+        bot_id.to_string(),
+    ));
+    tokio::spawn(remove_stale_bot(
+        server.clone(),
+        bot_id.to_string(),
+        watchdog,
+        api_resp_pending,
+    ));
+}
+
+/// 心跳超时后移除 Bot，再次收到上报时重新添加
+async fn remove_stale_bot(
+    server: HttpServer,
+    bot_id: String,
+    watchdog: HeartbeatWatchdog,
+    api_resp_pending: crate::ApiRespPending,
+) {
+    watchdog.stale().await;
+    event!(Level::WARN, "Bot [{}] heartbeat timeout", bot_id.red());
+    {
+        // 仅移除本连接的记录，已重新上报的 Bot 保持不变
+        let mut bots = server.bots.lock().unwrap();
+        if matches!(bots.get(&bot_id), Some((w, _)) if w.same(&watchdog)) {
+            bots.remove(&bot_id);
+        }
+    }
+    api_resp_pending.lock().unwrap().clear();
+    server
+        .action_sender
+        .send(crate::Action::RemoveBot {
+            bot_id,
+            api_resp_pending,
+        })
+        .await
+        .ok();
+}
+
+/// 将 Bot 调用的 Api 转为 HTTP 请求
+async fn handler_http_api(
+    http_api: Option<String>,
+    access_token: String,
+    codec: Codec,
+    api_resp_pending: crate::ApiRespPending,
+    mut api_receiver: mpsc::Receiver<ApiChannelItem>,
+    bot_id: String,
+) {
+    let client = http_client();
+    while let Some(data) = api_receiver.recv().await {
+        match data {
+            // Onebot Api
+            ApiChannelItem::Api(api) => {
+                if let Some(http_api) = &http_api {
+                    tokio::spawn(call_http_api(
+                        client.clone(),
+                        http_api.clone(),
+                        access_token.clone(),
+                        codec.clone(),
+                        api,
+                        api_resp_pending.clone(),
+                    ));
+                } else {
+                    event!(
+                        Level::WARN,
+                        "Bot [{}] has no http_server configured, drop Api {:?}",
+                        bot_id.red(),
+                        api
+                    );
+                }
+            }
+            _ => {
+                event!(
+                    Level::WARN,
+                    "{}",
+                    "HTTP Api 接受端接收到错误Event或TimeOut消息".bright_red()
+                );
+            } // 忽视 event 该 receiver 永不应该收到 event
+        }
+    }
+}
+
+#[cfg(feature = "tls")]
+type HttpClient = Client<hyper_tls::HttpsConnector<hyper::client::HttpConnector>>;
+#[cfg(not(feature = "tls"))]
+type HttpClient = Client<hyper::client::HttpConnector>;
+
+/// https 需要启用 tls feature
+#[cfg(feature = "tls")]
+fn http_client() -> HttpClient {
+    Client::builder().build(hyper_tls::HttpsConnector::new())
+}
+
+#[cfg(not(feature = "tls"))]
+fn http_client() -> HttpClient {
+    Client::new()
+}
+
+async fn call_http_api(
+    client: HttpClient,
+    http_api: String,
+    access_token: String,
+    codec: Codec,
+    api: crate::api::Api,
+    api_resp_pending: crate::ApiRespPending,
+) {
+    let echo = api.get_echo();
+    let mut api = codec.encode(&api);
+    let action = api["action"].as_str().unwrap_or_default().to_string();
+    // v11 POST 至 /:action 仅携带 params，v12 POST 至根路径携带完整 action
+    let (uri, body) = match codec {
+        Codec::V11 => {
+            let params = match api["params"].take() {
+                serde_json::Value::Null => serde_json::json!({}),
+                params => params,
+            };
+            (
+                format!("{}/{}", http_api.trim_end_matches('/'), action),
+                params,
+            )
+        }
+        Codec::V12(_) => (http_api, api),
+    };
+
+    let mut req = Request::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .header("Content-Type", "application/json");
+    if !access_token.is_empty() {
+        req = req.header("Authorization", format!("Bearer {}", access_token));
+    }
+    // 调用失败时立即通知等待中的调用方，避免等待超时
+    let fail = |e: String| {
+        event!(Level::WARN, "HTTP Api {} failed {}", action, e);
+        super::utils::send_api_error(&api_resp_pending, &echo, crate::ApiError::Http(e));
+    };
+    let req = match req.body(Body::from(body.to_string())) {
+        Ok(req) => req,
+        Err(e) => return fail(format!("build request {}", e)),
+    };
+
+    let body = match client.request(req).await {
+        Ok(resp) => hyper::body::to_bytes(resp.into_body()).await,
+        Err(e) => return fail(e.to_string()),
+    };
+
+    // HTTP 响应不含 echo，补全后交由 Bot 匹配
+    let resp = body
+        .map_err(|e| e.to_string())
+        .and_then(|body| {
+            serde_json::from_slice::<serde_json::Value>(&body).map_err(|e| e.to_string())
+        })
+        .and_then(|mut resp| {
+            resp["echo"] = serde_json::Value::String(echo.clone());
+            match codec.decode_value(resp) {
+                Some(RecvItem::ApiResp(resp)) => Ok(resp),
+                _ => Err("not a Onebot Api response".to_string()),
+            }
+        });
+    match resp {
+        Ok(resp) => super::utils::send_api_resp(&api_resp_pending, resp),
+        Err(e) => fail(format!("resp error {}", e)),
+    }
+}
+
+#[tokio::test]
+async fn http_api_error_test() {
+    let api = crate::api::Api::get_status();
+    let echo = api.get_echo();
+    let api_resp_pending = crate::ApiRespPending::default();
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+    api_resp_pending.lock().unwrap().insert(echo.clone(), sender);
+    // 端口 1 无服务监听，连接失败应立即返回而非等待超时
+    call_http_api(
+        http_client(),
+        "http://127.0.0.1:1".to_string(),
+        String::new(),
+        Codec::V11,
+        api,
+        api_resp_pending.clone(),
+    )
+    .await;
+    assert!(matches!(receiver.await, Ok(Err(crate::ApiError::Http(_)))));
+    assert!(api_resp_pending.lock().unwrap().is_empty());
+}
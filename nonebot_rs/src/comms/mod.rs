@@ -1,9 +1,14 @@
+pub mod heartbeat;
+pub mod http;
 pub mod revs_ws;
 pub mod utils;
+pub mod v12;
 pub mod ws;
 
 pub async fn strat_comms(nb: &crate::Nonebot) {
     let access_token = nb.config.gen_access_token();
+    let heartbeat_missed = nb.config.global.heartbeat_missed;
+    let protocols = nb.config.gen_protocols();
 
     if let Some(ws_server_config) = &nb.config.ws_server {
         tokio::spawn(revs_ws::run(
@@ -12,6 +17,39 @@ pub async fn strat_comms(nb: &crate::Nonebot) {
             nb.event_sender.clone(),
             nb.action_sender.clone(),
             access_token.clone(),
+            protocols.clone(),
+            heartbeat_missed,
+            nb.shutdown_receiver(),
+        ));
+    }
+
+    if let Some(http_server_config) = &nb.config.http_server {
+        let mut http_apis = std::collections::HashMap::new();
+        if let Some(bots) = &nb.config.bots {
+            for (bot_id, bot_config) in bots {
+                if bot_config.http_server.starts_with("https://") && !cfg!(feature = "tls") {
+                    tracing::event!(
+                        tracing::Level::ERROR,
+                        "Bot [{}] http_server {} requires tls feature",
+                        bot_id,
+                        bot_config.http_server
+                    );
+                } else if !bot_config.http_server.is_empty() {
+                    http_apis.insert(bot_id.clone(), bot_config.http_server.clone());
+                }
+            }
+        }
+        tokio::spawn(http::run(
+            http_server_config.host,
+            http_server_config.port,
+            nb.event_sender.clone(),
+            nb.action_sender.clone(),
+            access_token.clone(),
+            nb.config.gen_secret(),
+            http_apis,
+            protocols,
+            heartbeat_missed,
+            nb.shutdown_receiver(),
         ));
     }
 
@@ -24,6 +62,10 @@ pub async fn strat_comms(nb: &crate::Nonebot) {
                     nb.event_sender.clone(),
                     nb.action_sender.clone(),
                     access_token.clone(),
+                    bot_config.reconnect.clone(),
+                    bot_config.protocol,
+                    heartbeat_missed,
+                    nb.shutdown_receiver(),
                 ));
             }
         }
@@ -1,19 +1,24 @@
-use super::utils::handler_web_socket;
-use crate::{ActionSender, EventSender};
+use super::utils::{handler_web_socket, Codec};
+use crate::config::{Protocol, Protocols};
+use crate::{ActionSender, EventSender, ShutdownReceiver};
 use colored::*;
 use http::Response as HttpResponse;
 use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::{mpsc, watch};
+use tokio::sync::mpsc;
 use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
 use tracing::{event, Level};
 
 /// start Reverse WebSocket Server
+#[allow(clippy::too_many_arguments)]
 pub async fn run(
     host: std::net::Ipv4Addr,
     port: u16,
     event_sender: EventSender,
     action_sender: ActionSender,
     access_token: crate::config::AccessToken,
+    protocols: Protocols,
+    heartbeat_missed: u32,
+    mut shutdown: ShutdownReceiver,
 ) {
     // bind address to start Tcp server
     let try_socket = TcpListener::bind(std::net::SocketAddrV4::new(host, port)).await;
@@ -22,7 +27,11 @@ pub async fn run(
 
     // lopp wait for connect
     loop {
-        match listener.accept().await {
+        let accept = tokio::select! {
+            accept = listener.accept() => accept,
+            _ = shutdown.changed() => return,
+        };
+        match accept {
             Ok((stream, _)) => {
                 event!(Level::TRACE, "Get a TCP connect");
                 tokio::spawn(accept_connection(
@@ -30,6 +39,9 @@ pub async fn run(
                     event_sender.clone(),
                     action_sender.clone(),
                     access_token.clone(),
+                    protocols.clone(),
+                    heartbeat_missed,
+                    shutdown.clone(),
                 ));
             }
             Err(e) => event!(Level::WARN, "TCP connect error {}", e),
@@ -43,6 +55,9 @@ async fn accept_connection(
     event_sender: EventSender,
     action_sender: ActionSender,
     access_token: crate::config::AccessToken,
+    protocols: Protocols,
+    heartbeat_missed: u32,
+    shutdown: ShutdownReceiver,
 ) {
     // check peer address
     stream
@@ -51,35 +66,44 @@ async fn accept_connection(
     let mut output_bot_id = String::new();
 
     // callback to check headers && get bot_id
-    let callback =
-        |req: &Request, resp: Response| -> Result<Response, HttpResponse<Option<String>>> {
-            let headers = req.headers();
-            if let (Some(bot_id), Some(client_role), Some(user_agent)) = (
-                headers.get("X-Self-ID"),
-                headers.get("X-Client-Role"),
-                headers.get("User-Agent"),
-            ) {
-                let bot_id = bot_id.to_str().unwrap();
-                output_bot_id = bot_id.to_owned();
-                let client_role = client_role.to_str().unwrap();
-                let user_agent = user_agent.to_str().unwrap();
-                let auth: Option<String> = headers
-                    .get("Authorization")
-                    .map(|auth| auth.to_str().unwrap().to_owned());
+    let callback = |req: &Request,
+                    mut resp: Response|
+     -> Result<Response, HttpResponse<Option<String>>> {
+        let headers = req.headers();
+        if let (Some(bot_id), Some(user_agent)) =
+            (headers.get("X-Self-ID"), headers.get("User-Agent"))
+        {
+            let bot_id = bot_id.to_str().unwrap();
+            output_bot_id = bot_id.to_owned();
+            // Onebot v12 不要求 X-Client-Role
+            let client_role = headers
+                .get("X-Client-Role")
+                .and_then(|role| role.to_str().ok())
+                .unwrap_or_default();
+            let universal = client_role == "Universal" || protocols.get(bot_id) == Protocol::V12;
+            let user_agent = user_agent.to_str().unwrap();
+            let auth: Option<String> = headers
+                .get("Authorization")
+                .map(|auth| auth.to_str().unwrap().to_owned());
 
-                if client_role == "Universal" && access_token.check_auth(bot_id, auth) {
-                    event!(
-                        Level::INFO,
-                        "{} Client {} is connectted. The client type is {}",
-                        user_agent.bright_yellow(),
-                        bot_id.red(),
-                        client_role.bright_cyan()
-                    );
-                    return Ok(resp);
+            if universal && access_token.check_auth(bot_id, auth) {
+                // Onebot v12 客户端要求回应 Sec-WebSocket-Protocol
+                if let Some(sub_protocol) = headers.get("Sec-WebSocket-Protocol") {
+                    resp.headers_mut()
+                        .insert("Sec-WebSocket-Protocol", sub_protocol.clone());
                 }
+                event!(
+                    Level::INFO,
+                    "{} Client {} is connectted. The client type is {}",
+                    user_agent.bright_yellow(),
+                    bot_id.red(),
+                    client_role.bright_cyan()
+                );
+                return Ok(resp);
             }
-            Err(HttpResponse::new(None))
-        };
+        }
+        Err(HttpResponse::new(None))
+    };
 
     // Upgrade TcpStream to WebSocketStream
     let ws_stream = tokio_tungstenite::accept_hdr_async(stream, callback)
@@ -88,12 +112,7 @@ async fn accept_connection(
 
     // build channel
     let (sender, receiver) = mpsc::channel(32);
-    let (apiresp_watch_sender, api_resp_watcher) = watch::channel(crate::api_resp::ApiResp {
-        status: "init".to_string(),
-        retcode: 0,
-        data: crate::api_resp::RespData::None,
-        echo: "".to_string(),
-    });
+    let api_resp_pending = crate::ApiRespPending::default();
 
     // add bot to Nonebot
     action_sender
@@ -101,7 +120,7 @@ async fn accept_connection(
             bot_id: output_bot_id.clone(),
             api_sender: sender,
             action_sender: action_sender.clone(),
-            api_resp_watcher: api_resp_watcher,
+            api_resp_pending: api_resp_pending.clone(),
         })
         .await
         .unwrap();
@@ -111,9 +130,12 @@ async fn accept_connection(
         ws_stream,
         event_sender,
         action_sender,
-        apiresp_watch_sender,
+        api_resp_pending,
         receiver,
-        output_bot_id,
+        output_bot_id.clone(),
+        Codec::new(protocols.get(&output_bot_id)),
+        heartbeat_missed,
+        shutdown,
     )
     .await;
 }
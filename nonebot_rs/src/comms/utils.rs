@@ -1,34 +1,49 @@
-use crate::{event::Event, ActionSender, EventSender};
-use async_recursion::async_recursion;
+use super::heartbeat::HeartbeatWatchdog;
+use super::v12::V12Adapter;
+use crate::config::Protocol;
+use crate::event::{Event, RecvItem};
+use crate::{ActionSender, EventSender, ShutdownReceiver};
 use colored::*;
 use futures_util::{stream::SplitStream, SinkExt, StreamExt};
-use tokio::{
-    net::TcpStream,
-    sync::{broadcast, watch},
-};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::broadcast;
 use tokio_tungstenite::{tungstenite::Message as TuMessage, WebSocketStream};
 use tracing::{event, Level};
 
-pub async fn handler_web_socket(
-    socket: WebSocketStream<TcpStream>,
+#[allow(clippy::too_many_arguments)]
+pub async fn handler_web_socket<S>(
+    socket: WebSocketStream<S>,
     event_sender: EventSender,
     action_sender: ActionSender,
-    apiresp_watch_sender: tokio::sync::watch::Sender<crate::ApiResp>,
+    api_resp_pending: crate::ApiRespPending,
     mut api_receiver: tokio::sync::mpsc::Receiver<crate::ApiChannelItem>,
     bot_id: String,
-) {
+    codec: Codec,
+    heartbeat_missed: u32,
+    mut shutdown: ShutdownReceiver,
+) where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
     // 将 websocket 接收流与发送流分离
     let (mut sink, mut stream) = socket.split();
+    let watchdog = HeartbeatWatchdog::new(heartbeat_missed);
     // 接收消息
     let another_event_sender = event_sender.clone();
+    let another_action_sender = action_sender.clone();
+    let another_api_resp_pending = api_resp_pending.clone();
+    let another_bot_id = bot_id.clone();
+    let another_watchdog = watchdog.clone();
+    let another_codec = codec.clone();
     let income = async move {
         loop {
             let r = stream_recv(
                 stream,
                 &another_event_sender,
-                &action_sender,
-                &apiresp_watch_sender,
-                bot_id.clone(),
+                &another_action_sender,
+                &another_api_resp_pending,
+                &another_watchdog,
+                &another_codec,
+                another_bot_id.clone(),
             )
             .await;
             if let Some(s) = r {
@@ -40,12 +55,17 @@ pub async fn handler_web_socket(
     };
     // 发送消息
     let outcome = async move {
-        while let Some(data) = api_receiver.recv().await {
+        while let Some(data) = tokio::select! {
+            data = api_receiver.recv() => data,
+            _ = shutdown.changed() => None,
+        } {
             match data {
                 // Onebot Api
                 crate::ApiChannelItem::Api(api) => {
-                    let json_string = serde_json::to_string(&api).unwrap();
-                    sink.send(TuMessage::text(json_string)).await.unwrap();
+                    let json_string = codec.encode(&api).to_string();
+                    if let Err(e) = sink.send(TuMessage::text(json_string)).await {
+                        event!(Level::WARN, "Send Api failed {}", e);
+                    }
                 }
                 // temp Matcher event
                 crate::ApiChannelItem::MessageEvent(_) => {
@@ -65,59 +85,180 @@ pub async fn handler_web_socket(
                 } // 忽视 event 该 receiver 永不应该收到 event
             }
         }
+        // 关闭 WebSocket 连接
+        sink.close().await.ok();
     };
-    tokio::spawn(income);
-    outcome.await;
+    // 任一方向结束或心跳超时即视为连接结束
+    let mut income = tokio::spawn(income);
+    tokio::select! {
+        _ = outcome => {}
+        _ = &mut income => {}
+        _ = watchdog.stale() => {
+            event!(
+                Level::WARN,
+                "Bot [{}] heartbeat timeout, disconnecting",
+                bot_id.red()
+            );
+            api_resp_pending.lock().unwrap().clear();
+            action_sender
+                .send(crate::Action::RemoveBot {
+                    bot_id,
+                    api_resp_pending,
+                })
+                .await
+                .ok();
+        }
+    }
+    income.abort();
 }
 
-async fn stream_recv(
-    stream: SplitStream<WebSocketStream<TcpStream>>,
+async fn stream_recv<S>(
+    stream: SplitStream<WebSocketStream<S>>,
     event_sender: &EventSender,
     action_sender: &ActionSender,
-    apiresp_watch_sender: &watch::Sender<crate::api_resp::ApiResp>,
+    api_resp_pending: &crate::ApiRespPending,
+    watchdog: &HeartbeatWatchdog,
+    codec: &Codec,
     bot_id: String,
-) -> Option<SplitStream<WebSocketStream<TcpStream>>> {
+) -> Option<SplitStream<WebSocketStream<S>>>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
     let (msg, next_stream) = stream.into_future().await;
-    if let Some(msg) = msg {
-        use crate::event::RecvItem;
-        if let Ok(msg) = msg {
-            let data: serde_json::Result<RecvItem> = serde_json::from_str(msg.to_text().unwrap());
-            match data {
-                Ok(data) => match data {
-                    RecvItem::Event(event) => send_event(&event_sender, event).await,
-                    RecvItem::ApiResp(api_resp) => {
-                        apiresp_watch_sender.send(api_resp).unwrap();
+    match msg {
+        Some(Ok(msg)) => {
+            // 忽略 Ping Pong Close 等控制帧
+            if msg.is_text() || msg.is_binary() {
+                match codec.decode(&msg.into_data()) {
+                    Some(RecvItem::Event(event)) => {
+                        watchdog.feed(&event);
+                        send_event(event_sender, event).await
                     }
-                },
-                Err(e) => {
-                    event!(
-                        Level::ERROR,
-                        "Serialize msg failed! Msg:{:?}\nError:{}",
-                        msg.to_text().unwrap(),
-                        e
-                    );
-                    std::process::exit(101);
+                    Some(RecvItem::ApiResp(api_resp)) => send_api_resp(api_resp_pending, api_resp),
+                    None => {}
                 }
             }
-        } else {
+        }
+        // 连接出错或已关闭
+        _ => {
             event!(Level::WARN, "Bot [{}] disconnect", bot_id.to_string().red());
+            // 丢弃所有等待中的请求，调用方立即返回
+            api_resp_pending.lock().unwrap().clear();
+            // Nonebot 关闭时 Bot 已被移除
             action_sender
-                .send(crate::Action::RemoveBot { bot_id: bot_id })
+                .send(crate::Action::RemoveBot {
+                    bot_id,
+                    api_resp_pending: api_resp_pending.clone(),
+                })
                 .await
-                .unwrap();
+                .ok();
             return None;
         }
     }
     Some(next_stream)
 }
 
-#[async_recursion]
-pub async fn send_event(sender: &broadcast::Sender<Event>, e: Event) -> () {
-    match sender.send(e) {
-        Ok(_) => (),
-        Err(broadcast::error::SendError(_)) => {
-            event!(Level::ERROR, "EventChannel is full out of cache!");
-            std::process::exit(101);
+/// 根据 echo 将 ApiResp 交给等待中的调用方
+pub fn send_api_resp(api_resp_pending: &crate::ApiRespPending, api_resp: crate::ApiResp) {
+    crate::builtin::resp_logger(&api_resp);
+    let sender = api_resp_pending.lock().unwrap().remove(&api_resp.echo);
+    if let Some(sender) = sender {
+        sender.send(Ok(api_resp)).ok();
+    }
+}
+
+/// 根据 echo 将调用失败交给等待中的调用方
+pub fn send_api_error(
+    api_resp_pending: &crate::ApiRespPending,
+    echo: &str,
+    error: crate::ApiError,
+) {
+    let sender = api_resp_pending.lock().unwrap().remove(echo);
+    if let Some(sender) = sender {
+        sender.send(Err(error)).ok();
+    }
+}
+
+/// 解析 Onebot v11 上报数据
+///
+/// 无法识别的上报保留为 `Event::Unknown`，非 JSON 数据将被丢弃
+pub fn parse_recv_item(data: &[u8]) -> Option<RecvItem> {
+    Codec::V11.decode(data)
+}
+
+/// 按 Bot 协议版本编解码 Onebot 数据
+#[derive(Debug, Clone)]
+pub enum Codec {
+    V11,
+    V12(V12Adapter),
+}
+
+impl Codec {
+    pub fn new(protocol: Protocol) -> Self {
+        match protocol {
+            Protocol::V11 => Codec::V11,
+            Protocol::V12 => Codec::V12(V12Adapter::default()),
+        }
+    }
+
+    /// 解析上报数据，非 JSON 数据将被丢弃
+    pub fn decode(&self, data: &[u8]) -> Option<RecvItem> {
+        match serde_json::from_slice(data) {
+            Ok(value) => self.decode_value(value),
+            Err(e) => {
+                event!(
+                    Level::ERROR,
+                    "Serialize msg failed! Msg:{:?}\nError:{}",
+                    String::from_utf8_lossy(data),
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    /// 解析上报数据，无法识别的上报保留为 `Event::Unknown`
+    pub fn decode_value(&self, value: serde_json::Value) -> Option<RecvItem> {
+        let value = match self {
+            Codec::V11 => value,
+            Codec::V12(adapter) => adapter.to_v11(value),
+        };
+        match serde_json::from_value::<RecvItem>(value.clone()) {
+            Ok(item) => Some(item),
+            Err(e) => {
+                event!(Level::WARN, "Unknown msg:{}\nError:{}", value, e);
+                if value.get("post_type").is_some() || value.get("detail_type").is_some() {
+                    Some(RecvItem::Event(Event::Unknown(value)))
+                } else {
+                    None
+                }
+            }
         }
     }
+
+    /// 序列化 Api 为 `{action, params, echo}`
+    pub fn encode(&self, api: &crate::api::Api) -> serde_json::Value {
+        let api = serde_json::to_value(api).unwrap();
+        match self {
+            Codec::V11 => api,
+            Codec::V12(adapter) => adapter.api_to_v12(api),
+        }
+    }
+}
+
+/// 广播 Event，没有 Plugin 接收时丢弃该 Event
+pub async fn send_event(sender: &broadcast::Sender<Event>, e: Event) {
+    if let Err(broadcast::error::SendError(e)) = sender.send(e) {
+        event!(Level::WARN, "No plugin receiving, drop event {:?}", e);
+    }
+}
+
+#[test]
+fn unknown_event_test() {
+    let test_str = "{\"post_type\":\"message_sent\",\"self_id\":11,\"time\":1631193409}";
+    match parse_recv_item(test_str.as_bytes()) {
+        Some(RecvItem::Event(Event::Unknown(v))) => assert_eq!(v["post_type"], "message_sent"),
+        _ => panic!("unknown event should be kept"),
+    }
+    assert!(parse_recv_item(b"not json").is_none());
 }
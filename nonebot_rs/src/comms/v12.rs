@@ -0,0 +1,351 @@
+//! Onebot v12 与 nbrs 内部（v11）数据结构互转
+//!
+//! 事件中的 `type`/`detail_type` 转为 `post_type`/`message_type` 等字段，
+//! `mention`/`mention_all`/`voice` 等消息段转为对应的 v11 消息段，
+//! v12 字符串 message_id 映射为 i32，调用 Api 时再映射回原 ID。
+
+use serde_json::{json, Map, Value};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// 最多保留的 message_id 映射数量
+const MESSAGE_ID_CAPACITY: usize = 4096;
+
+/// v12 字符串 message_id 与 i32 message_id 映射表
+#[derive(Debug, Default)]
+struct MessageIdMap {
+    v11_ids: HashMap<String, i32>,
+    v12_ids: HashMap<i32, String>,
+    order: VecDeque<i32>,
+    next: i32,
+}
+
+impl MessageIdMap {
+    fn v11_id(&mut self, v12_id: &str) -> i32 {
+        if let Some(id) = self.v11_ids.get(v12_id) {
+            return *id;
+        }
+        self.next = self.next.wrapping_add(1).max(1);
+        let id = self.next;
+        if self.order.len() >= MESSAGE_ID_CAPACITY {
+            if let Some(old) = self.order.pop_front() {
+                if let Some(old_v12_id) = self.v12_ids.remove(&old) {
+                    self.v11_ids.remove(&old_v12_id);
+                }
+            }
+        }
+        self.v11_ids.insert(v12_id.to_string(), id);
+        self.v12_ids.insert(id, v12_id.to_string());
+        self.order.push_back(id);
+        id
+    }
+
+    fn v12_id(&self, v11_id: i64) -> String {
+        match self.v12_ids.get(&(v11_id as i32)) {
+            Some(id) => id.clone(),
+            None => v11_id.to_string(),
+        }
+    }
+}
+
+/// 单个 v12 连接的转换器
+#[derive(Debug, Clone, Default)]
+pub struct V12Adapter {
+    message_ids: Arc<Mutex<MessageIdMap>>,
+}
+
+fn as_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn as_i64(value: &Value) -> Option<i64> {
+    match value {
+        Value::Number(n) => n.as_i64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+impl V12Adapter {
+    fn message_id_to_v11(&self, id: &Value) -> Value {
+        match as_string(id) {
+            Some(id) => json!(self.message_ids.lock().unwrap().v11_id(&id)),
+            None => Value::Null,
+        }
+    }
+
+    fn message_id_to_v12(&self, id: &Value) -> Value {
+        match as_i64(id) {
+            Some(id) => json!(self.message_ids.lock().unwrap().v12_id(id)),
+            None => id.clone(),
+        }
+    }
+
+    /// v12 事件或 Api 响应转为 v11 格式
+    pub fn to_v11(&self, value: Value) -> Value {
+        match value.get("type").and_then(|t| t.as_str()) {
+            Some(_) if value.get("detail_type").is_some() => self.event_to_v11(value),
+            _ => self.resp_to_v11(value),
+        }
+    }
+
+    fn event_to_v11(&self, mut value: Value) -> Value {
+        let event = match value.as_object_mut() {
+            Some(event) => event,
+            None => return value,
+        };
+        let type_ = event
+            .remove("type")
+            .and_then(|t| as_string(&t))
+            .unwrap_or_default();
+        let detail_type = event.remove("detail_type").unwrap_or(Value::Null);
+        // v12 时间戳为浮点秒
+        if let Some(time) = event.get("time").and_then(|t| t.as_f64()) {
+            event.insert("time".to_string(), json!(time as i64));
+        }
+        if let Some(Value::String(sub_type)) = event.get("sub_type") {
+            if sub_type.is_empty() {
+                event.remove("sub_type");
+            }
+        }
+        if let Some(id) = event.get("message_id").cloned() {
+            event.insert("message_id".to_string(), self.message_id_to_v11(&id));
+        }
+        let user_id = event.get("user_id").cloned().unwrap_or(Value::Null);
+        let post_type = match type_.as_str() {
+            "message" => {
+                event.insert("message_type".to_string(), detail_type.clone());
+                if let Some(message) = event.remove("message") {
+                    event.insert("message".to_string(), self.message_to_v11(message));
+                }
+                let raw_message = event.remove("alt_message").unwrap_or_else(|| json!(""));
+                event.insert("raw_message".to_string(), raw_message);
+                event.entry("font").or_insert(json!(0));
+                let mut sender = json!({
+                    "user_id": user_id,
+                    "nickname": "",
+                    "sex": "unknown",
+                    "age": 0,
+                });
+                if detail_type == "group" {
+                    for key in ["card", "area", "level", "title"].iter() {
+                        sender[*key] = json!("");
+                    }
+                    sender["role"] = json!("member");
+                }
+                event.entry("sender").or_insert(sender);
+                event.entry("sub_type").or_insert(json!(""));
+                "message"
+            }
+            "notice" => {
+                event.insert("notice_type".to_string(), detail_type);
+                event.entry("user_id").or_insert(json!(""));
+                "notice"
+            }
+            "request" => {
+                event.insert("request_type".to_string(), detail_type);
+                event.entry("user_id").or_insert(json!(""));
+                let comment = event.remove("message").unwrap_or_else(|| json!(""));
+                event.entry("comment").or_insert(comment);
+                let flag = event.remove("request_id").unwrap_or_else(|| json!(""));
+                event.entry("flag").or_insert(flag);
+                "request"
+            }
+            "meta" => {
+                event.insert("meta_event_type".to_string(), detail_type);
+                "meta_event"
+            }
+            _ => return value,
+        };
+        event.insert("post_type".to_string(), json!(post_type));
+        value
+    }
+
+    fn resp_to_v11(&self, mut value: Value) -> Value {
+        let resp = match value.as_object_mut() {
+            Some(resp) => resp,
+            None => return value,
+        };
+        if let Some(message) = resp.remove("message") {
+            resp.entry("msg").or_insert(message);
+        }
+        if let Some(Value::Object(data)) = resp.get_mut("data") {
+            if let Some(id) = data.get("message_id").cloned() {
+                data.insert("message_id".to_string(), self.message_id_to_v11(&id));
+            }
+            if let Some(name) = data.remove("user_name") {
+                data.entry("nickname").or_insert(name);
+            }
+        }
+        value
+    }
+
+    /// v12 消息段转为 v11 消息段
+    fn message_to_v11(&self, message: Value) -> Value {
+        let segments = match message {
+            Value::Array(segments) => segments,
+            Value::String(text) => return json!([{"type": "text", "data": {"text": text}}]),
+            other => return other,
+        };
+        let segments = segments
+            .into_iter()
+            .map(|segment| {
+                let type_ = segment["type"].as_str().unwrap_or_default().to_string();
+                let data = &segment["data"];
+                match type_.as_str() {
+                    "mention" => json!({"type": "at", "data": {"qq": as_string(&data["user_id"])}}),
+                    "mention_all" => json!({"type": "at", "data": {"qq": "all"}}),
+                    "image" | "video" => {
+                        json!({"type": type_, "data": {"file": data["file_id"]}})
+                    }
+                    "voice" | "audio" => {
+                        json!({"type": "record", "data": {"file": data["file_id"]}})
+                    }
+                    "reply" => json!({
+                        "type": "reply",
+                        "data": {"id": self.message_id_to_v11(&data["message_id"]).to_string()}
+                    }),
+                    "location" => json!({
+                        "type": "location",
+                        "data": {
+                            "lat": data["latitude"].to_string(),
+                            "lon": data["longitude"].to_string(),
+                            "title": data["title"],
+                            "content": data["content"],
+                        }
+                    }),
+                    _ => segment,
+                }
+            })
+            .collect();
+        Value::Array(segments)
+    }
+
+    /// v11 消息段转为 v12 消息段
+    fn message_to_v12(&self, message: Value) -> Value {
+        let segments = match message {
+            Value::Array(segments) => segments,
+            other => return other,
+        };
+        let segments = segments
+            .into_iter()
+            .map(|segment| {
+                let type_ = segment["type"].as_str().unwrap_or_default().to_string();
+                let data = &segment["data"];
+                match type_.as_str() {
+                    "at" if data["qq"] == "all" => json!({"type": "mention_all", "data": {}}),
+                    "at" => json!({"type": "mention", "data": {"user_id": data["qq"]}}),
+                    "image" | "video" => {
+                        json!({"type": type_, "data": {"file_id": data["file"]}})
+                    }
+                    "record" => json!({"type": "voice", "data": {"file_id": data["file"]}}),
+                    "reply" => json!({
+                        "type": "reply",
+                        "data": {"message_id": self.message_id_to_v12(&data["id"])}
+                    }),
+                    "location" => json!({
+                        "type": "location",
+                        "data": {
+                            "latitude": as_string(&data["lat"]).and_then(|l| l.parse::<f64>().ok()),
+                            "longitude": as_string(&data["lon"]).and_then(|l| l.parse::<f64>().ok()),
+                            "title": data["title"],
+                            "content": data["content"],
+                        }
+                    }),
+                    _ => segment,
+                }
+            })
+            .collect();
+        Value::Array(segments)
+    }
+
+    /// v11 Api 转为 v12 action
+    pub fn api_to_v12(&self, mut api: Value) -> Value {
+        let action = api["action"].as_str().unwrap_or_default().to_string();
+        let mut params = match api["params"].take() {
+            Value::Object(params) => params,
+            _ => Map::new(),
+        };
+        if let Some(message) = params.remove("message") {
+            params.insert("message".to_string(), self.message_to_v12(message));
+        }
+        // v12 不支持 auto_escape
+        params.remove("auto_escape");
+        if let Some(id) = params.get("message_id").cloned() {
+            params.insert("message_id".to_string(), self.message_id_to_v12(&id));
+        }
+        let action = match action.as_str() {
+            "send_private_msg" => {
+                params.insert("detail_type".to_string(), json!("private"));
+                "send_message"
+            }
+            "send_group_msg" => {
+                params.insert("detail_type".to_string(), json!("group"));
+                "send_message"
+            }
+            "send_msg" => {
+                let detail_type = params.remove("message_type").unwrap_or(Value::Null);
+                params.insert("detail_type".to_string(), detail_type);
+                "send_message"
+            }
+            "delete_msg" => "delete_message",
+            "get_msg" => "get_message",
+            "get_login_info" => "get_self_info",
+            "get_stranger_info" => "get_user_info",
+            "set_group_leave" => "leave_group",
+            "set_group_kick" => "kick_group_member",
+            action => action,
+        };
+        api["action"] = json!(action);
+        api["params"] = Value::Object(params);
+        api
+    }
+}
+
+#[test]
+fn v12_event_test() {
+    use crate::event::{Event, MessageEvent};
+    let adapter = V12Adapter::default();
+    let event = json!({
+        "id": "b6e65187-5ac0-489c-b431-53078e9d2bbb",
+        "impl": "walle-q",
+        "platform": "qq",
+        "self_id": "123234",
+        "time": 1632847927.599013,
+        "type": "message",
+        "detail_type": "group",
+        "sub_type": "",
+        "message_id": "6283",
+        "message": [
+            {"type": "mention", "data": {"user_id": "123234"}},
+            {"type": "text", "data": {"text": " OneBot is not a bot"}}
+        ],
+        "alt_message": "@123234 OneBot is not a bot",
+        "group_id": "12467",
+        "user_id": "123456788"
+    });
+    let event: Event = serde_json::from_value(adapter.to_v11(event)).unwrap();
+    match event {
+        Event::Message(MessageEvent::Group(g)) => {
+            assert_eq!(g.platform.as_deref(), Some("qq"));
+            assert_eq!(g.group_id, "12467");
+            assert_eq!(adapter.message_id_to_v12(&json!(g.message_id)), "6283");
+        }
+        _ => panic!("unexpected event"),
+    }
+
+    let api = serde_json::to_value(crate::api::Api::send_group_msg(crate::api::SendGroupMsg {
+        group_id: "12467".to_string(),
+        message: vec![crate::message::Message::at("all".to_string())],
+        auto_escape: false,
+    }))
+    .unwrap();
+    let api = adapter.api_to_v12(api);
+    assert_eq!(api["action"], "send_message");
+    assert_eq!(api["params"]["detail_type"], "group");
+    assert_eq!(api["params"]["message"][0]["type"], "mention_all");
+}
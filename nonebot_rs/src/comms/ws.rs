@@ -1,117 +1,193 @@
-use super::utils::handler_web_socket;
-use crate::{event::Event, matcher::prelude::SelfId, ActionSender, EventSender};
-use async_recursion::async_recursion;
+use super::utils::Codec;
+use super::utils::{handler_web_socket, send_event};
+use crate::config::{Protocol, ReconnectConfig};
+use crate::event::{Event, NbEvent, RecvItem, SelfId};
+use crate::{ActionSender, EventSender, ShutdownReceiver};
 use colored::*;
 use futures_util::StreamExt;
-use tokio::{
-    net::TcpStream,
-    sync::{mpsc, watch},
-};
+use tokio::sync::mpsc;
 use tracing::{event, Level};
 
-use tokio_tungstenite::{client_async, tungstenite::handshake::client::Request};
+use tokio_tungstenite::{connect_async, tungstenite::handshake::client::Request};
 
-#[async_recursion]
+/// 连接正向 WS，断开后按 `ReconnectConfig` 指数退避重连
+#[allow(clippy::too_many_arguments)]
 pub async fn run(
     url: String,
     bot_id: String,
     event_sender: EventSender,
     action_sender: ActionSender,
     access_token: crate::config::AccessToken,
+    reconnect: ReconnectConfig,
+    protocol: Protocol,
+    heartbeat_missed: u32,
+    mut shutdown: ShutdownReceiver,
 ) {
-    single_socket(
-        &url,
-        &bot_id,
-        event_sender.clone(),
-        action_sender.clone(),
-        access_token.clone(),
-    )
-    .await;
-    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
-    run(url, bot_id, event_sender, action_sender, access_token).await;
+    let mut failures: u32 = 0;
+    loop {
+        send_event(
+            &event_sender,
+            Event::Nonebot(NbEvent::BotConnecting {
+                bot_id: bot_id.clone(),
+                url: url.clone(),
+                attempt: failures + 1,
+            }),
+        )
+        .await;
+
+        match single_socket(
+            &url,
+            &bot_id,
+            event_sender.clone(),
+            action_sender.clone(),
+            access_token.clone(),
+            protocol,
+            heartbeat_missed,
+            shutdown.clone(),
+        )
+        .await
+        {
+            Ok(_) => failures = 0,
+            Err(ConnectError::InvalidUrl(e)) => {
+                event!(
+                    Level::ERROR,
+                    "Invalid ws_server {} for Bot {}: {}",
+                    url,
+                    bot_id.red(),
+                    e
+                );
+                return;
+            }
+            Err(ConnectError::Failed(e)) => {
+                failures += 1;
+                event!(Level::WARN, "Connect to {} failed: {}", url, e);
+            }
+        }
+
+        // 关闭后不再重连
+        if *shutdown.borrow() {
+            return;
+        }
+        if reconnect.max_attempts != 0 && failures >= reconnect.max_attempts {
+            event!(
+                Level::ERROR,
+                "Connect to {} failed {} times, stop reconnecting",
+                url,
+                failures
+            );
+            send_event(
+                &event_sender,
+                Event::Nonebot(NbEvent::BotReconnectFailed {
+                    bot_id,
+                    url,
+                    attempts: failures,
+                }),
+            )
+            .await;
+            return;
+        }
+
+        let delay = reconnect.delay(failures);
+        event!(Level::INFO, "Reconnecting to {} in {:?}", url, delay);
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = shutdown.changed() => return,
+        }
+    }
 }
 
+/// 正向 WS 连接失败原因
+#[derive(Debug)]
+pub enum ConnectError {
+    /// 地址无效，重连无意义
+    InvalidUrl(String),
+    /// 连接或握手失败
+    Failed(String),
+}
+
+/// 连接正向 WS 并处理至断开，连接成功过则返回 Ok
+#[allow(clippy::too_many_arguments)]
 pub async fn single_socket(
     url: &str,
     bot_id: &str,
     event_sender: EventSender,
     action_sender: ActionSender,
     access_token: crate::config::AccessToken,
-) -> () {
+    protocol: Protocol,
+    heartbeat_missed: u32,
+    shutdown: ShutdownReceiver,
+) -> Result<(), ConnectError> {
     let req = Request::builder()
         .uri(url)
         .header("Authorization", access_token.get(bot_id))
         .body(())
-        .unwrap();
-    let host = req.uri().host().unwrap().to_string();
-    let port = req
-        .uri()
-        .port_u16()
-        .or_else(|| match req.uri().scheme_str() {
-            Some("wss") => Some(443),
-            Some("ws") => Some(80),
-            _ => None,
-        })
-        .ok_or("unable to get port")
-        .unwrap();
-    let addr = format!("{}:{}", host, port);
+        .map_err(|e| ConnectError::InvalidUrl(e.to_string()))?;
+    #[cfg(not(feature = "tls"))]
+    if req.uri().scheme_str() == Some("wss") {
+        return Err(ConnectError::InvalidUrl(
+            "wss:// requires the tls feature".to_string(),
+        ));
+    }
+    match req.uri().scheme_str() {
+        Some("ws") | Some("wss") if req.uri().host().is_some() => {}
+        _ => {
+            return Err(ConnectError::InvalidUrl(
+                "expect ws:// or wss://".to_string(),
+            ))
+        }
+    }
 
     event!(Level::INFO, "Connecting to {}", url);
 
-    let tcp_stream = match TcpStream::connect(addr).await {
-        Ok(s) => s,
-        Err(_) => return (),
+    let (mut stream, _) = connect_async(req)
+        .await
+        .map_err(|e| ConnectError::Failed(e.to_string()))?;
+
+    let codec = Codec::new(protocol);
+    let event = match stream.next().await {
+        Some(Ok(msg)) => match codec.decode(&msg.into_data()) {
+            Some(RecvItem::Event(event)) => event,
+            _ => {
+                return Err(ConnectError::Failed(
+                    "first frame is not a Onebot event".to_string(),
+                ))
+            }
+        },
+        Some(Err(e)) => return Err(ConnectError::Failed(e.to_string())),
+        None => return Err(ConnectError::Failed("connection closed".to_string())),
     };
+    let bot_id = event.get_self_id();
+
+    event!(Level::INFO, "Connectted to Bot {} Server", bot_id.red());
 
     // build channel
     let (sender, receiver) = mpsc::channel(32);
-    let (apiresp_watch_sender, api_resp_watcher) = watch::channel(crate::api_resp::ApiResp {
-        status: "init".to_string(),
-        retcode: 0,
-        data: crate::api_resp::RespData::None,
-        echo: "".to_string(),
-    });
-
-    let ws_stream = client_async(req, tcp_stream).await;
-    match ws_stream {
-        Ok((mut stream, _)) => {
-            // let headers = resp.headers();
-            // println!("{:?}", headers);
-            if let Some(data) = stream.next().await {
-                match data {
-                    Ok(msg) => {
-                        let msg = msg.to_text().unwrap();
-                        let event: Event = serde_json::from_str(msg).unwrap();
-                        let bot_id = event.get_self_id();
+    let api_resp_pending = crate::ApiRespPending::default();
 
-                        event!(Level::INFO, "Connectted to Bot {} Server", bot_id.red());
-
-                        // add bot to Nonebot
-                        action_sender
-                            .send(crate::Action::AddBot {
-                                bot_id: bot_id.clone(),
-                                api_sender: sender,
-                                action_sender: action_sender.clone(),
-                                api_resp_watcher: api_resp_watcher,
-                            })
-                            .await
-                            .unwrap();
+    // add bot to Nonebot
+    action_sender
+        .send(crate::Action::AddBot {
+            bot_id: bot_id.clone(),
+            api_sender: sender,
+            action_sender: action_sender.clone(),
+            api_resp_pending: api_resp_pending.clone(),
+        })
+        .await
+        .ok();
+    send_event(&event_sender, event).await;
 
-                        // handle WebSocketStream
-                        handler_web_socket(
-                            stream,
-                            event_sender,
-                            action_sender,
-                            apiresp_watch_sender,
-                            receiver,
-                            bot_id,
-                        )
-                        .await;
-                    }
-                    Err(_) => {}
-                }
-            }
-        }
-        Err(_) => {}
-    }
+    // handle WebSocketStream
+    handler_web_socket(
+        stream,
+        event_sender,
+        action_sender,
+        api_resp_pending,
+        receiver,
+        bot_id,
+        codec,
+        heartbeat_missed,
+        shutdown,
+    )
+    .await;
+    Ok(())
 }
@@ -15,6 +15,8 @@ pub struct NbConfig {
     pub bots: Option<HashMap<String, BotConfig>>,
     /// 反向 WS 服务器设置
     pub ws_server: Option<WebSocketServerConfig>,
+    /// HTTP POST 上报服务器设置
+    pub http_server: Option<HttpServerConfig>,
     #[serde(skip)]
     config: Config, // save the full config
 }
@@ -41,6 +43,18 @@ pub struct WebSocketServerConfig {
     access_token: String,
 }
 
+/// HTTP POST 上报服务器设置
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HttpServerConfig {
+    /// Host
+    pub host: std::net::Ipv4Addr,
+    /// Port
+    pub port: u16,
+    /// Onebot HTTP POST 签名密钥
+    #[serde(default)]
+    secret: String,
+}
+
 /// nbrs 全局配置
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GlobalConfig {
@@ -54,6 +68,20 @@ pub struct GlobalConfig {
     pub nicknames: Vec<String>,
     /// 全局命令起始符设置
     pub command_starts: Vec<String>,
+    /// 超过该数量个心跳间隔未收到心跳即断开连接，0 为不检测
+    #[serde(default = "default_heartbeat_missed")]
+    pub heartbeat_missed: u32,
+    /// 数据存储目录
+    #[serde(default = "default_data_dir")]
+    pub data_dir: String,
+}
+
+fn default_heartbeat_missed() -> u32 {
+    3
+}
+
+fn default_data_dir() -> String {
+    crate::storage::DEFAULT_DATA_DIR.to_string()
 }
 
 /// nbrs bot 配置
@@ -77,6 +105,100 @@ pub struct BotConfig {
     /// 正向 WS 地址
     #[serde(default)]
     pub ws_server: String,
+    /// HTTP API 地址
+    #[serde(default)]
+    pub http_server: String,
+    #[serde(default)]
+    secret: String, // Onebot HTTP POST signature
+    /// 正向 WS 重连设置
+    #[serde(default)]
+    pub reconnect: ReconnectConfig,
+    /// Onebot 协议版本 v11|v12
+    #[serde(default)]
+    pub protocol: Protocol,
+    /// 消息发送限速设置
+    #[serde(default)]
+    pub throttle: ThrottleConfig,
+}
+
+/// Onebot 协议版本
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum Protocol {
+    /// Onebot v11（go-cqhttp 等）
+    #[default]
+    #[serde(rename = "v11")]
+    V11,
+    /// Onebot v12
+    #[serde(rename = "v12")]
+    V12,
+}
+
+/// 消息发送限速设置
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ThrottleConfig {
+    /// 每秒最多发送消息数，0 为不限制
+    #[serde(default)]
+    pub rate: f64,
+    /// 同一群组或用户两条消息的最小间隔（毫秒），0 为不限制
+    #[serde(default)]
+    pub target_interval: u64,
+}
+
+impl ThrottleConfig {
+    /// 是否启用限速
+    pub fn enabled(&self) -> bool {
+        self.rate > 0.0 || self.target_interval > 0
+    }
+}
+
+/// 正向 WS 重连设置
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReconnectConfig {
+    /// 首次重连间隔（秒），此后每次失败翻倍
+    #[serde(default = "default_reconnect_interval")]
+    pub interval: u64,
+    /// 最大重连间隔（秒）
+    #[serde(default = "default_reconnect_max_interval")]
+    pub max_interval: u64,
+    /// 最大连续重连次数，0 为不限制
+    #[serde(default)]
+    pub max_attempts: u32,
+}
+
+fn default_reconnect_interval() -> u64 {
+    5
+}
+
+fn default_reconnect_max_interval() -> u64 {
+    60
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        ReconnectConfig {
+            interval: default_reconnect_interval(),
+            max_interval: default_reconnect_max_interval(),
+            max_attempts: 0,
+        }
+    }
+}
+
+impl ReconnectConfig {
+    /// 第 failures 次连续失败后的重连等待时间，随机抖动于 [delay / 2, delay]
+    ///
+    /// interval 与 max_interval 至少按 1 秒计算，避免连续快速重连
+    pub fn delay(&self, failures: u32) -> std::time::Duration {
+        use rand::Rng;
+        let exp = failures.saturating_sub(1).min(16);
+        let delay = self
+            .interval
+            .max(1)
+            .saturating_mul(1 << exp)
+            .min(self.max_interval.max(1))
+            .saturating_mul(1000);
+        let delay = rand::thread_rng().gen_range(delay / 2..=delay);
+        std::time::Duration::from_millis(delay)
+    }
 }
 
 impl Default for BotConfig {
@@ -88,6 +210,11 @@ impl Default for BotConfig {
             command_starts: vec![],
             access_token: String::default(),
             ws_server: String::default(),
+            http_server: String::default(),
+            secret: String::default(),
+            reconnect: ReconnectConfig::default(),
+            protocol: Protocol::default(),
+            throttle: ThrottleConfig::default(),
         }
     }
 }
@@ -101,6 +228,8 @@ impl Default for NbConfig {
                 superusers: vec![],
                 nicknames: vec![],
                 command_starts: vec!["/".to_string()],
+                heartbeat_missed: default_heartbeat_missed(),
+                data_dir: default_data_dir(),
             },
             bots: None,
             config: Config::default(),
@@ -109,6 +238,7 @@ impl Default for NbConfig {
                 port: 8088,
                 access_token: String::default(),
             }),
+            http_server: None,
         }
     }
 }
@@ -166,12 +296,21 @@ impl NbConfig {
             command_starts: self.global.command_starts.clone(),
             access_token: String::default(),
             ws_server: String::default(),
+            http_server: String::default(),
+            secret: String::default(),
+            reconnect: ReconnectConfig::default(),
+            protocol: Protocol::default(),
+            throttle: ThrottleConfig::default(),
         };
 
         if let Some(server_config) = &self.ws_server {
             rbotconfig.access_token = server_config.access_token.clone();
         }
 
+        if let Some(server_config) = &self.http_server {
+            rbotconfig.secret = server_config.secret.clone();
+        }
+
         if let Some(bots_config) = &self.bots {
             if let Some(bot_config) = bots_config.get(bot_id) {
                 if !bot_config.superusers.is_empty() {
@@ -186,6 +325,12 @@ impl NbConfig {
                 if !bot_config.access_token.is_empty() {
                     rbotconfig.access_token = bot_config.access_token.clone();
                 }
+                if !bot_config.secret.is_empty() {
+                    rbotconfig.secret = bot_config.secret.clone();
+                }
+                rbotconfig.reconnect = bot_config.reconnect.clone();
+                rbotconfig.protocol = bot_config.protocol;
+                rbotconfig.throttle = bot_config.throttle.clone();
             }
         }
         rbotconfig
@@ -210,6 +355,49 @@ impl NbConfig {
         }
         at
     }
+
+    pub fn gen_secret(&self) -> Secret {
+        let mut s = Secret {
+            global: if let Some(http_server_config) = &self.http_server {
+                http_server_config.secret.clone()
+            } else {
+                String::default()
+            },
+            bots: HashMap::default(),
+        };
+        if let Some(bots) = &self.bots {
+            for (bot_id, bot) in bots {
+                if !bot.secret.is_empty() {
+                    s.bots.insert(bot_id.to_string(), bot.secret.to_string());
+                }
+            }
+        }
+        s
+    }
+
+    pub fn gen_protocols(&self) -> Protocols {
+        let mut p = Protocols {
+            bots: HashMap::default(),
+        };
+        if let Some(bots) = &self.bots {
+            for (bot_id, bot) in bots {
+                p.bots.insert(bot_id.to_string(), bot.protocol);
+            }
+        }
+        p
+    }
+}
+
+#[derive(Clone)]
+pub struct Protocols {
+    pub bots: HashMap<String, Protocol>,
+}
+
+impl Protocols {
+    /// 未配置的 Bot 默认使用 v11
+    pub fn get(&self, bot_id: &str) -> Protocol {
+        self.bots.get(bot_id).copied().unwrap_or_default()
+    }
 }
 
 #[derive(Clone)]
@@ -265,3 +453,66 @@ impl AccessToken {
         result
     }
 }
+
+#[derive(Clone)]
+pub struct Secret {
+    pub global: String,
+    pub bots: HashMap<String, String>,
+}
+
+impl Secret {
+    pub fn get(&self, bot_id: &str) -> &str {
+        if let Some(s) = self.bots.get(bot_id) {
+            s
+        } else {
+            &self.global
+        }
+    }
+
+    /// 校验 HTTP POST 上报的 X-Signature（HMAC-SHA1）
+    pub fn check_signature(&self, bot_id: &str, signature: Option<String>, body: &[u8]) -> bool {
+        use hmac::{Hmac, Mac, NewMac};
+
+        let secret = self.get(bot_id);
+        if secret.is_empty() {
+            return true;
+        }
+
+        let mut result = false;
+        if let Some(signature) = &signature {
+            if let Ok(sig) = hex::decode(signature.trim_start_matches("sha1=")) {
+                let mut mac = Hmac::<sha1::Sha1>::new_from_slice(secret.as_bytes()).unwrap();
+                mac.update(body);
+                result = mac.verify(&sig).is_ok();
+            }
+        }
+
+        if !result {
+            event!(
+                Level::WARN,
+                "Signature match fail Bot:[{}] Signature:{:?}",
+                bot_id.red(),
+                signature
+            );
+        }
+
+        result
+    }
+}
+
+#[test]
+fn reconnect_delay_test() {
+    let reconnect = ReconnectConfig::default();
+    let ms = |failures| reconnect.delay(failures).as_millis();
+    assert!((2500..=5000).contains(&ms(1)));
+    assert!((5000..=10000).contains(&ms(2)));
+    assert!((30000..=60000).contains(&ms(10)));
+    assert!((30000..=60000).contains(&ms(u32::MAX)));
+
+    let reconnect = ReconnectConfig {
+        interval: 0,
+        max_interval: 0,
+        max_attempts: 0,
+    };
+    assert!(reconnect.delay(1).as_millis() >= 500);
+}
@@ -0,0 +1,598 @@
+use crate::builtin::matches::Captures;
+use crate::command::{CommandArgs, CommandError};
+use crate::message::MessageChain;
+use crate::utils::id_deserializer;
+use serde::{Deserialize, Serialize};
+
+/// 按类型字段解析为枚举，无法解析时保留原始 JSON
+macro_rules! typed_events {
+    (
+        $(#[$enum_doc:meta])*
+        $name:ident, $kind:ident, $from:ident, $type_key:literal;
+        $($(#[$doc:meta])* $variant:ident => $type_:literal $(/ $sub_type:literal)?,)+
+    ) => {
+        $(#[$enum_doc])*
+        #[derive(Debug, Clone)]
+        pub enum $name {
+            $($(#[$doc])* $variant($variant),)+
+            /// 未知类型或字段不符，保留原始 JSON
+            Unknown(serde_json::Value),
+        }
+
+        impl $name {
+            #[doc = concat!("上报的 ", $type_key)]
+            pub fn type_(&self) -> &str {
+                match self {
+                    $($name::$variant(_) => $type_,)+
+                    $name::Unknown(v) => v[$type_key].as_str().unwrap_or_default(),
+                }
+            }
+
+            fn from_value(value: serde_json::Value) -> Self {
+                let type_ = value[$type_key].as_str().unwrap_or_default();
+                #[allow(unused_variables)]
+                let sub_type = value["sub_type"].as_str().unwrap_or_default();
+                $(
+                    if type_ == $type_ $(&& sub_type == $sub_type)? {
+                        return match serde_json::from_value(value.clone()) {
+                            Ok(e) => $name::$variant(e),
+                            Err(_) => $name::Unknown(value),
+                        };
+                    }
+                )+
+                $name::Unknown(value)
+            }
+
+            fn to_value(&self) -> serde_json::Result<serde_json::Value> {
+                let (mut value, sub_type): (serde_json::Value, Option<&str>) = match self {
+                    $($name::$variant(e) => (serde_json::to_value(e)?, typed_events!(@sub $($sub_type)?)),)+
+                    $name::Unknown(v) => return Ok(v.clone()),
+                };
+                if let serde_json::Value::Object(map) = &mut value {
+                    map.insert($type_key.to_string(), self.type_().into());
+                    if let Some(sub_type) = sub_type {
+                        map.insert("sub_type".to_string(), sub_type.into());
+                    }
+                }
+                Ok(value)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(d: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                Ok($name::from_value(serde_json::Value::deserialize(d)?))
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                self.to_value()
+                    .map_err(serde::ser::Error::custom)?
+                    .serialize(s)
+            }
+        }
+
+        #[doc = concat!("`", stringify!($name), "` 各变体内容类型")]
+        pub trait $kind: Sized {
+            fn $from(e: &$name) -> Option<&Self>;
+        }
+
+        $(
+            impl $kind for $variant {
+                fn $from(e: &$name) -> Option<&Self> {
+                    match e {
+                        $name::$variant(e) => Some(e),
+                        _ => None,
+                    }
+                }
+            }
+        )+
+    };
+    (@sub) => { None };
+    (@sub $sub_type:literal) => { Some($sub_type) };
+}
+
+/// 通知事件类型
+pub mod notice;
+/// 请求事件类型
+pub mod request;
+
+pub use notice::{Notice, NoticeKind};
+pub use request::{Request, RequestKind};
+
+/// WebSocket 接受数据枚举 Event || ApiResp
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RecvItem {
+    Event(Event),
+    ApiResp(crate::api_resp::ApiResp),
+}
+
+/// Onebot 事件
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "post_type")]
+pub enum Event {
+    /// 消息事件
+    #[serde(rename = "message")]
+    Message(MessageEvent),
+
+    /// 通知事件
+    #[serde(rename = "notice")]
+    Notice(NoticeEvent),
+
+    /// 请求事件
+    #[serde(rename = "request")]
+    Request(RequestEvent),
+
+    /// 元事件
+    #[serde(rename = "meta_event")]
+    Meta(MetaEvent),
+
+    /// Nonebot 内部事件
+    #[serde(skip)]
+    Nonebot(NbEvent),
+
+    /// 无法解析的上报，保留原始 JSON
+    #[serde(skip)]
+    Unknown(serde_json::Value),
+}
+
+/// Nonebot Event
+#[derive(Debug, Clone)]
+pub enum NbEvent {
+    BotConnect {
+        bot: crate::Bot,
+    },
+    BotDisconnect {
+        bot: crate::Bot,
+    },
+    /// 正向 WS 正在连接，attempt 为连续尝试次数
+    BotConnecting {
+        bot_id: String,
+        url: String,
+        attempt: u32,
+    },
+    /// 正向 WS 连续失败次数达到上限，不再重连
+    BotReconnectFailed {
+        bot_id: String,
+        url: String,
+        attempts: u32,
+    },
+}
+
+/// 消息事件
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "message_type")]
+pub enum MessageEvent {
+    /// 私聊事件
+    #[serde(rename = "private")]
+    Private(PrivateMessageEvent),
+
+    /// 群消息事件
+    #[serde(rename = "group")]
+    Group(GroupMessageEvent),
+}
+
+impl MessageEvent {
+    /// 消息事件时间戳
+    #[allow(dead_code)]
+    pub fn get_time(&self) -> i64 {
+        match self {
+            MessageEvent::Private(p) => p.time,
+            MessageEvent::Group(g) => g.time,
+        }
+    }
+
+    /// 命令解析成功时的参数
+    pub fn get_command_args(&self) -> Option<&CommandArgs> {
+        match self {
+            MessageEvent::Private(p) => p.command_args.as_deref(),
+            MessageEvent::Group(g) => g.command_args.as_deref(),
+        }
+        .and_then(|args| args.as_ref().ok())
+    }
+
+    /// 正则匹配捕获组
+    pub fn get_captures(&self) -> Option<&Captures> {
+        match self {
+            MessageEvent::Private(p) => p.captures.as_deref(),
+            MessageEvent::Group(g) => g.captures.as_deref(),
+        }
+    }
+
+    /// 设置正则匹配捕获组
+    pub fn set_captures(&mut self, captures: Captures) {
+        let captures = Some(Box::new(captures));
+        match self {
+            MessageEvent::Private(p) => p.captures = captures,
+            MessageEvent::Group(g) => g.captures = captures,
+        }
+    }
+
+    /// 设置命令解析结果
+    pub fn set_command_args(&mut self, args: Result<CommandArgs, CommandError>) {
+        *self.command_args_mut() = Some(Box::new(args));
+    }
+
+    pub(crate) fn command_args_mut(
+        &mut self,
+    ) -> &mut Option<Box<Result<CommandArgs, CommandError>>> {
+        match self {
+            MessageEvent::Private(p) => &mut p.command_args,
+            MessageEvent::Group(g) => &mut g.command_args,
+        }
+    }
+
+    /// 消息事件字符串格式消息
+    #[allow(dead_code)]
+    pub fn get_raw_message(&self) -> &str {
+        match self {
+            MessageEvent::Private(p) => &p.raw_message,
+            MessageEvent::Group(g) => &g.raw_message,
+        }
+    }
+
+    /// 消息事件设置字符串格式消息
+    #[allow(dead_code)]
+    pub fn set_raw_message(&mut self, new_raw_message: String) -> MessageEvent {
+        match self {
+            MessageEvent::Private(p) => {
+                p.raw_message = new_raw_message;
+                MessageEvent::Private(p.clone())
+            }
+            MessageEvent::Group(g) => {
+                g.raw_message = new_raw_message;
+                MessageEvent::Group(g.clone())
+            }
+        }
+    }
+
+    /// 消息事件消息链
+    #[allow(dead_code)]
+    pub fn get_message(&self) -> &MessageChain {
+        match self {
+            MessageEvent::Private(p) => &p.message,
+            MessageEvent::Group(g) => &g.message,
+        }
+    }
+
+    /// 消息事件发送者昵称
+    #[allow(dead_code)]
+    pub fn get_sender_nickname(&self) -> &str {
+        match self {
+            MessageEvent::Private(p) => &p.sender.nickname,
+            MessageEvent::Group(g) => &g.sender.nickname,
+        }
+    }
+}
+
+/// 私聊消息事件
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PrivateMessageEvent {
+    /// Event 时间戳
+    pub time: i64,
+    /// 收到事件的机器人 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub self_id: String,
+    /// Onebot v12 实现名称
+    #[serde(rename = "impl", default, skip_serializing_if = "Option::is_none")]
+    pub impl_: Option<String>,
+    /// Onebot v12 平台名称
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub platform: Option<String>,
+    /// 消息子类型
+    pub sub_type: String,
+    /// 消息 ID
+    pub message_id: i32,
+    /// 发送者 ID
+    #[serde(deserialize_with = "id_deserializer")]
+    pub user_id: String,
+    /// 消息内容
+    pub message: MessageChain,
+    /// 原生消息内容
+    pub raw_message: String,
+    /// 字体
+    pub font: i32,
+    /// 发送者消息
+    pub sender: PrivateSender,
+    /// 命令解析结果
+    #[serde(skip)]
+    pub command_args: Option<Box<Result<CommandArgs, CommandError>>>,
+    /// 正则匹配捕获组
+    #[serde(skip)]
+    pub captures: Option<Box<Captures>>,
+}
+
+/// 私聊消息事件发送者
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PrivateSender {
+    /// 发送者 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub user_id: String,
+    /// 昵称
+    pub nickname: String,
+    /// 性别 male|female|unkown
+    pub sex: String,
+    /// 年龄
+    pub age: i32,
+}
+
+/// 群消息事件
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GroupMessageEvent {
+    /// Event 时间戳
+    pub time: i64,
+    /// 收到事件的机器人 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub self_id: String,
+    /// Onebot v12 实现名称
+    #[serde(rename = "impl", default, skip_serializing_if = "Option::is_none")]
+    pub impl_: Option<String>,
+    /// Onebot v12 平台名称
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub platform: Option<String>,
+    /// 消息子类型
+    pub sub_type: String,
+    /// 消息 ID
+    pub message_id: i32,
+    /// 群消息群号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub group_id: String,
+    /// 发送者 ID
+    #[serde(deserialize_with = "id_deserializer")]
+    pub user_id: String,
+    /// 匿名消息 非匿名消息为空
+    pub anonymous: Option<Anoymous>,
+    /// 消息内容
+    pub message: MessageChain,
+    /// 原生消息内容
+    pub raw_message: String,
+    /// 字体
+    pub font: i32,
+    /// 发送者消息
+    pub sender: GroupSender,
+    /// 命令解析结果
+    #[serde(skip)]
+    pub command_args: Option<Box<Result<CommandArgs, CommandError>>>,
+    /// 正则匹配捕获组
+    #[serde(skip)]
+    pub captures: Option<Box<Captures>>,
+}
+
+/// 群消息事件发送者
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GroupSender {
+    /// 发送者 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub user_id: String,
+    /// 昵称
+    pub nickname: String,
+    /// 群名片|备注
+    pub card: String,
+    /// 性别 male|female|unkown
+    pub sex: String,
+    /// 年龄
+    pub age: i32,
+    /// 地区
+    pub area: String,
+    /// 成员等级
+    pub level: String,
+    /// 角色 owner|admin|member
+    pub role: String,
+    /// 专属头衔
+    pub title: String,
+}
+
+/// 消息事件匿名字段
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Anoymous {
+    /// 匿名用户 ID
+    #[serde(deserialize_with = "id_deserializer")]
+    pub id: String,
+    /// 匿名用户名称
+    pub name: String,
+    /// 匿名用户 flag
+    pub flag: String,
+}
+
+/// 通知事件
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NoticeEvent {
+    /// Event 时间戳
+    pub time: i64,
+    /// 收到事件的机器人 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub self_id: String,
+    /// Onebot v12 实现名称
+    #[serde(rename = "impl", default, skip_serializing_if = "Option::is_none")]
+    pub impl_: Option<String>,
+    /// Onebot v12 平台名称
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub platform: Option<String>,
+    /// 按 notice_type 与 sub_type 解析的通知内容
+    #[serde(flatten)]
+    pub notice: Notice,
+}
+
+impl NoticeEvent {
+    /// 获取指定类型的通知内容，类型不符时返回 None
+    ///
+    /// ```ignore
+    /// if let Some(increase) = event.get::<GroupIncrease>() {
+    ///     println!("{} joined {}", increase.user_id, increase.group_id);
+    /// }
+    /// ```
+    pub fn get<T>(&self) -> Option<&T>
+    where
+        T: NoticeKind,
+    {
+        T::from_notice(&self.notice)
+    }
+}
+
+/// 通知事件文件字段
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct File {
+    /// 文件 ID
+    pub id: String,
+    /// 文件名
+    pub name: String,
+    /// 文件大小（字节数）
+    pub size: i64,
+    /// 用途未知
+    pub busid: i64,
+}
+
+/// 请求事件
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RequestEvent {
+    /// Event 时间戳
+    pub time: i64,
+    /// 收到事件的机器人 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub self_id: String,
+    /// Onebot v12 实现名称
+    #[serde(rename = "impl", default, skip_serializing_if = "Option::is_none")]
+    pub impl_: Option<String>,
+    /// Onebot v12 平台名称
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub platform: Option<String>,
+    /// 按 request_type 解析的请求内容
+    #[serde(flatten)]
+    pub request: Request,
+}
+
+impl RequestEvent {
+    /// 获取指定类型的请求内容，类型不符时返回 None
+    pub fn get<T>(&self) -> Option<&T>
+    where
+        T: RequestKind,
+    {
+        T::from_request(&self.request)
+    }
+}
+
+/// 元事件
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MetaEvent {
+    /// Event 时间戳
+    pub time: i64,
+    /// 收到事件的机器人 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub self_id: String,
+    /// Onebot v12 实现名称
+    #[serde(rename = "impl", default, skip_serializing_if = "Option::is_none")]
+    pub impl_: Option<String>,
+    /// Onebot v12 平台名称
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub platform: Option<String>,
+    /// 元事件类型 lifecycle|heartbeat
+    pub meta_event_type: String,
+    /// 事件子类型
+    pub sub_type: Option<String>,
+    /// 状态信息
+    pub status: Option<Status>,
+    /// 下次心跳间隔，单位毫秒
+    pub interval: Option<i64>,
+}
+
+#[test]
+fn de_test() {
+    let test_str = "{\"group_id\":101,\"message_id\":111,\"notice_type\":\"group_recall\",\"operator_id\":11,\"post_type\":\"notice\",\"self_id\":11,\"time\":1631193409,\"user_id\":11}\n";
+    let _meta: Event = serde_json::from_str(test_str).unwrap();
+}
+
+/// 元事件状态字段
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Status {
+    /// 是否在线，None 表示无法查询
+    pub online: Option<bool>,
+    /// 运行状态是否符合预期
+    pub good: bool,
+}
+
+/// `get_user_id()` trait
+pub trait UserId {
+    fn get_user_id(&self) -> String;
+}
+
+impl UserId for MessageEvent {
+    fn get_user_id(&self) -> String {
+        match self {
+            MessageEvent::Private(p) => p.user_id.to_string(),
+            MessageEvent::Group(g) => g.user_id.to_string(),
+        }
+    }
+}
+
+impl UserId for NoticeEvent {
+    fn get_user_id(&self) -> String {
+        self.notice.user_id().unwrap_or_default().to_string()
+    }
+}
+
+impl UserId for RequestEvent {
+    fn get_user_id(&self) -> String {
+        self.request.user_id().unwrap_or_default().to_string()
+    }
+}
+
+/// `get_self_id()` trait
+pub trait SelfId {
+    fn get_self_id(&self) -> String;
+}
+
+impl SelfId for MessageEvent {
+    fn get_self_id(&self) -> String {
+        match self {
+            MessageEvent::Private(p) => p.self_id.clone(),
+            MessageEvent::Group(g) => g.self_id.clone(),
+        }
+    }
+}
+
+impl SelfId for RequestEvent {
+    fn get_self_id(&self) -> String {
+        self.self_id.clone()
+    }
+}
+
+impl SelfId for NoticeEvent {
+    fn get_self_id(&self) -> String {
+        self.self_id.clone()
+    }
+}
+
+impl SelfId for MetaEvent {
+    fn get_self_id(&self) -> String {
+        self.self_id.clone()
+    }
+}
+
+impl SelfId for Event {
+    fn get_self_id(&self) -> String {
+        match self {
+            Event::Message(e) => e.get_self_id(),
+            Event::Request(e) => e.get_self_id(),
+            Event::Notice(e) => e.get_self_id(),
+            Event::Meta(e) => e.get_self_id(),
+            Event::Nonebot(e) => match e {
+                NbEvent::BotConnect { bot } => bot.bot_id.clone(),
+                NbEvent::BotDisconnect { bot } => bot.bot_id.clone(),
+                NbEvent::BotConnecting { bot_id, .. } => bot_id.clone(),
+                NbEvent::BotReconnectFailed { bot_id, .. } => bot_id.clone(),
+            },
+            Event::Unknown(v) => match &v["self_id"] {
+                serde_json::Value::String(s) => s.clone(),
+                serde_json::Value::Number(n) => n.to_string(),
+                _ => String::default(),
+            },
+        }
+    }
+}
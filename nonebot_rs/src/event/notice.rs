@@ -0,0 +1,420 @@
+use super::File;
+use crate::utils::{id_deserializer, option_id_deserializer, value_id};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+typed_events! {
+    /// 通知内容，按 notice_type 与 sub_type 区分
+    ///
+    /// ```ignore
+    /// match &event.notice {
+    ///     Notice::GroupIncrease(n) => println!("{} joined {}", n.user_id, n.group_id),
+    ///     Notice::Poke(n) => println!("{} poked {}", n.user_id, n.target_id),
+    ///     _ => {}
+    /// }
+    /// ```
+    Notice, NoticeKind, from_notice, "notice_type";
+    /// 群文件上传
+    GroupUpload => "group_upload",
+    /// 群管理员变动
+    GroupAdmin => "group_admin",
+    /// 群成员减少
+    GroupDecrease => "group_decrease",
+    /// 群成员增加
+    GroupIncrease => "group_increase",
+    /// 群禁言
+    GroupBan => "group_ban",
+    /// 好友添加
+    FriendAdd => "friend_add",
+    /// 群消息撤回
+    GroupRecall => "group_recall",
+    /// 好友消息撤回
+    FriendRecall => "friend_recall",
+    /// 戳一戳
+    Poke => "notify" / "poke",
+    /// 群红包运气王
+    LuckyKing => "notify" / "lucky_king",
+    /// 群成员荣誉变更
+    Honor => "notify" / "honor",
+    /// 群成员头衔变更（go-cqhttp）
+    GroupTitle => "notify" / "title",
+    /// 群成员名片更新（go-cqhttp）
+    GroupCard => "group_card",
+    /// 接收到离线文件（go-cqhttp）
+    OfflineFile => "offline_file",
+    /// 其他客户端在线状态变更（go-cqhttp）
+    ClientStatus => "client_status",
+    /// 精华消息变更（go-cqhttp）
+    Essence => "essence",
+}
+
+impl Notice {
+    /// 通知相关用户，无用户的通知返回 None
+    pub fn user_id(&self) -> Option<Cow<'_, str>> {
+        match self {
+            Notice::GroupUpload(n) => Some(n.user_id.as_str().into()),
+            Notice::GroupAdmin(n) => Some(n.user_id.as_str().into()),
+            Notice::GroupDecrease(n) => Some(n.user_id.as_str().into()),
+            Notice::GroupIncrease(n) => Some(n.user_id.as_str().into()),
+            Notice::GroupBan(n) => Some(n.user_id.as_str().into()),
+            Notice::FriendAdd(n) => Some(n.user_id.as_str().into()),
+            Notice::GroupRecall(n) => Some(n.user_id.as_str().into()),
+            Notice::FriendRecall(n) => Some(n.user_id.as_str().into()),
+            Notice::Poke(n) => Some(n.user_id.as_str().into()),
+            Notice::LuckyKing(n) => Some(n.user_id.as_str().into()),
+            Notice::Honor(n) => Some(n.user_id.as_str().into()),
+            Notice::GroupTitle(n) => Some(n.user_id.as_str().into()),
+            Notice::GroupCard(n) => Some(n.user_id.as_str().into()),
+            Notice::OfflineFile(n) => Some(n.user_id.as_str().into()),
+            Notice::ClientStatus(_) => None,
+            Notice::Essence(n) => Some(n.sender_id.as_str().into()),
+            Notice::Unknown(v) => value_id(&v["user_id"]),
+        }
+    }
+
+    /// 通知相关群组，非群通知返回 None
+    pub fn group_id(&self) -> Option<Cow<'_, str>> {
+        match self {
+            Notice::GroupUpload(n) => Some(n.group_id.as_str().into()),
+            Notice::GroupAdmin(n) => Some(n.group_id.as_str().into()),
+            Notice::GroupDecrease(n) => Some(n.group_id.as_str().into()),
+            Notice::GroupIncrease(n) => Some(n.group_id.as_str().into()),
+            Notice::GroupBan(n) => Some(n.group_id.as_str().into()),
+            Notice::GroupRecall(n) => Some(n.group_id.as_str().into()),
+            Notice::Poke(n) => n.group_id.as_deref().map(Cow::from),
+            Notice::LuckyKing(n) => Some(n.group_id.as_str().into()),
+            Notice::Honor(n) => Some(n.group_id.as_str().into()),
+            Notice::GroupTitle(n) => Some(n.group_id.as_str().into()),
+            Notice::GroupCard(n) => Some(n.group_id.as_str().into()),
+            Notice::Essence(n) => Some(n.group_id.as_str().into()),
+            Notice::FriendAdd(_)
+            | Notice::FriendRecall(_)
+            | Notice::OfflineFile(_)
+            | Notice::ClientStatus(_) => None,
+            Notice::Unknown(v) => value_id(&v["group_id"]),
+        }
+    }
+}
+
+/// 群文件上传
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GroupUpload {
+    /// 群号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub group_id: String,
+    /// 上传者 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub user_id: String,
+    /// 文件信息
+    pub file: File,
+}
+
+/// 群管理员变动类型
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AdminChange {
+    /// 设置管理员
+    Set,
+    /// 取消管理员
+    Unset,
+}
+
+/// 群管理员变动
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GroupAdmin {
+    pub sub_type: AdminChange,
+    /// 群号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub group_id: String,
+    /// 管理员 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub user_id: String,
+}
+
+/// 群成员减少类型
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DecreaseType {
+    /// 主动退群
+    Leave,
+    /// 成员被踢
+    Kick,
+    /// 登录号被踢
+    KickMe,
+}
+
+/// 群成员减少
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GroupDecrease {
+    pub sub_type: DecreaseType,
+    /// 群号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub group_id: String,
+    /// 操作者 QQ 号，主动退群时与 user_id 相同
+    #[serde(deserialize_with = "id_deserializer")]
+    pub operator_id: String,
+    /// 离开者 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub user_id: String,
+}
+
+/// 群成员增加类型
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IncreaseType {
+    /// 管理员同意入群
+    Approve,
+    /// 管理员邀请入群
+    Invite,
+}
+
+/// 群成员增加
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GroupIncrease {
+    pub sub_type: IncreaseType,
+    /// 群号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub group_id: String,
+    /// 操作者 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub operator_id: String,
+    /// 加入者 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub user_id: String,
+}
+
+/// 群禁言类型
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BanType {
+    /// 禁言
+    Ban,
+    /// 解除禁言
+    LiftBan,
+}
+
+/// 群禁言
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GroupBan {
+    pub sub_type: BanType,
+    /// 群号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub group_id: String,
+    /// 操作者 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub operator_id: String,
+    /// 被禁言 QQ 号，全员禁言时为 0
+    #[serde(deserialize_with = "id_deserializer")]
+    pub user_id: String,
+    /// 禁言时长，单位秒
+    pub duration: i64,
+}
+
+/// 好友添加
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FriendAdd {
+    /// 新添加好友 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub user_id: String,
+}
+
+/// 群消息撤回
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GroupRecall {
+    /// 群号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub group_id: String,
+    /// 消息发送者 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub user_id: String,
+    /// 操作者 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub operator_id: String,
+    /// 被撤回的消息 ID
+    pub message_id: i64,
+}
+
+/// 好友消息撤回
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FriendRecall {
+    /// 好友 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub user_id: String,
+    /// 被撤回的消息 ID
+    pub message_id: i64,
+}
+
+/// 戳一戳
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Poke {
+    /// 群号，好友戳一戳（go-cqhttp）为 None
+    #[serde(deserialize_with = "option_id_deserializer")]
+    #[serde(default)]
+    pub group_id: Option<String>,
+    /// 发送者 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub user_id: String,
+    /// 被戳者 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub target_id: String,
+}
+
+/// 群红包运气王
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LuckyKing {
+    /// 群号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub group_id: String,
+    /// 红包发送者 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub user_id: String,
+    /// 运气王 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub target_id: String,
+}
+
+/// 群荣誉类型
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HonorType {
+    /// 龙王
+    Talkative,
+    /// 群聊之火
+    Performer,
+    /// 快乐源泉
+    Emotion,
+}
+
+/// 群成员荣誉变更
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Honor {
+    /// 群号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub group_id: String,
+    /// 荣誉类型
+    pub honor_type: HonorType,
+    /// 成员 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub user_id: String,
+}
+
+/// 群成员头衔变更（go-cqhttp）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GroupTitle {
+    /// 群号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub group_id: String,
+    /// 成员 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub user_id: String,
+    /// 获得的新头衔
+    pub title: String,
+}
+
+/// 群成员名片更新（go-cqhttp）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GroupCard {
+    /// 群号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub group_id: String,
+    /// 成员 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub user_id: String,
+    /// 新名片
+    pub card_new: String,
+    /// 旧名片
+    pub card_old: String,
+}
+
+/// 离线文件信息（go-cqhttp）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OfflineFileInfo {
+    /// 文件名
+    pub name: String,
+    /// 文件大小（字节数）
+    pub size: i64,
+    /// 下载链接
+    pub url: String,
+}
+
+/// 接收到离线文件（go-cqhttp）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OfflineFile {
+    /// 发送者 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub user_id: String,
+    /// 文件信息
+    pub file: OfflineFileInfo,
+}
+
+/// 其他客户端在线状态变更（go-cqhttp）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClientStatus {
+    /// 客户端信息
+    pub client: serde_json::Value,
+    /// 当前是否在线
+    pub online: bool,
+}
+
+/// 精华消息变更类型（go-cqhttp）
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EssenceType {
+    /// 添加
+    Add,
+    /// 移出
+    Delete,
+}
+
+/// 精华消息变更（go-cqhttp）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Essence {
+    pub sub_type: EssenceType,
+    /// 群号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub group_id: String,
+    /// 消息发送者 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub sender_id: String,
+    /// 操作者 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub operator_id: String,
+    /// 消息 ID
+    pub message_id: i64,
+}
+
+#[test]
+fn notice_test() {
+    let event: super::Event = serde_json::from_str(
+        r#"{"post_type":"notice","notice_type":"notify","sub_type":"poke","time":0,"self_id":1,"group_id":10,"user_id":2,"target_id":1}"#,
+    )
+    .unwrap();
+    let event = match event {
+        super::Event::Notice(n) => n,
+        _ => panic!("not a notice"),
+    };
+    let poke = event.get::<Poke>().unwrap();
+    assert_eq!(poke.group_id.as_deref(), Some("10"));
+    assert_eq!(poke.target_id, "1");
+    assert!(event.get::<LuckyKing>().is_none());
+
+    let value = serde_json::to_value(&event).unwrap();
+    assert_eq!(value["notice_type"], "notify");
+    assert_eq!(value["sub_type"], "poke");
+
+    let event: super::NoticeEvent = serde_json::from_str(
+        r#"{"notice_type":"group_increase","sub_type":"approve","time":0,"self_id":1,"group_id":10,"operator_id":3,"user_id":2}"#,
+    )
+    .unwrap();
+    match &event.notice {
+        Notice::GroupIncrease(n) => assert_eq!(n.sub_type, IncreaseType::Approve),
+        n => panic!("unexpected {:?}", n),
+    }
+
+    let event: super::NoticeEvent =
+        serde_json::from_str(r#"{"notice_type":"unknown","time":0,"self_id":1,"user_id":2}"#)
+            .unwrap();
+    assert_eq!(event.notice.type_(), "unknown");
+    assert_eq!(event.notice.user_id().as_deref(), Some("2"));
+    assert_eq!(event.notice.group_id(), None);
+}
@@ -0,0 +1,79 @@
+use crate::utils::{id_deserializer, value_id};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+typed_events! {
+    /// 请求内容，按 request_type 区分
+    Request, RequestKind, from_request, "request_type";
+    /// 加好友请求
+    FriendRequest => "friend",
+    /// 加群请求或邀请
+    GroupRequest => "group",
+}
+
+impl Request {
+    /// 发送请求的用户
+    pub fn user_id(&self) -> Option<Cow<'_, str>> {
+        match self {
+            Request::FriendRequest(r) => Some(r.user_id.as_str().into()),
+            Request::GroupRequest(r) => Some(r.user_id.as_str().into()),
+            Request::Unknown(v) => value_id(&v["user_id"]),
+        }
+    }
+
+    /// 请求相关群组，加好友请求返回 None
+    pub fn group_id(&self) -> Option<Cow<'_, str>> {
+        match self {
+            Request::FriendRequest(_) => None,
+            Request::GroupRequest(r) => Some(r.group_id.as_str().into()),
+            Request::Unknown(v) => value_id(&v["group_id"]),
+        }
+    }
+
+    /// 请求 flag，处理请求时需要传入
+    pub fn flag(&self) -> Option<&str> {
+        match self {
+            Request::FriendRequest(r) => Some(&r.flag),
+            Request::GroupRequest(r) => Some(&r.flag),
+            Request::Unknown(v) => v["flag"].as_str(),
+        }
+    }
+}
+
+/// 加好友请求
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FriendRequest {
+    /// 发送请求的 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub user_id: String,
+    /// 验证信息
+    pub comment: String,
+    /// 请求 flag
+    pub flag: String,
+}
+
+/// 加群请求类型
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupRequestType {
+    /// 加群请求
+    Add,
+    /// 邀请登录号入群
+    Invite,
+}
+
+/// 加群请求或邀请
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GroupRequest {
+    pub sub_type: GroupRequestType,
+    /// 群号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub group_id: String,
+    /// 发送请求的 QQ 号
+    #[serde(deserialize_with = "id_deserializer")]
+    pub user_id: String,
+    /// 验证信息
+    pub comment: String,
+    /// 请求 flag
+    pub flag: String,
+}
@@ -26,18 +26,37 @@
 //! superusers = ["YourID"]      # 全局管理员账号
 //! nicknames = ["nickname"]     # 全局 Bot 昵称
 //! command_starts = ["/"]       # 全局命令起始符
+//! heartbeat_missed = 3         # 超过 N 个心跳间隔未收到心跳即断开连接，0 为不检测
+//! data_dir = "data"            # 数据存储目录
 //!
 //! [ws_server]                  # 反向 WS 服务器
 //! host = "127.0.0.1"           # 监听 host
 //! port = 8088                  # 监听 port
 //! access_token = "AccessToken" # 连接鉴权使用
 //!
+//! [http_server]                # HTTP POST 上报服务器（缺省不启用）
+//! host = "127.0.0.1"           # 监听 host
+//! port = 8089                  # 监听 port
+//! secret = "Secret"            # X-Signature 签名校验使用
+//!
 //! [bots.BotID]                 # Bot 设置
 //! superusers = ["YourID"]      # 管理员账户
 //! nicknames = ["nickname"]     # Bot 昵称
 //! command_starts = ["/"]       # 命令起始符
 //! ws_server = "server address" # 正向 WS 服务器地址（缺省不启用正向 WS 连接）
+//! http_server = "api address"  # HTTP API 地址（缺省该 Bot 无法通过 HTTP 调用 Api，https 需启用 tls feature）
 //! access_token = "AccessToken" # 连接鉴权使用
+//! secret = "Secret"            # X-Signature 签名校验使用
+//! protocol = "v11"             # Onebot 协议版本 v11|v12
+//!
+//! [bots.BotID.reconnect]       # 正向 WS 重连设置（wss 需启用 tls feature）
+//! interval = 5                 # 首次重连间隔（秒），此后指数退避并随机抖动
+//! max_interval = 60            # 最大重连间隔（秒）
+//! max_attempts = 0             # 最大连续重连次数，0 为不限制
+//!
+//! [bots.BotID.throttle]        # 消息发送限速设置（缺省不限速）
+//! rate = 1.0                   # 每秒最多发送消息数，0 为不限制
+//! target_interval = 1500       # 同一群组或用户的最小发送间隔（毫秒）
 //! ```
 //!
 //! ## Plugin
@@ -99,6 +118,44 @@
 //! }
 //! ```
 //!
+//! 声明式命令，参数解析失败时自动回复用法：
+//!
+//! ```ignore
+//! #[async_trait]
+//! impl Handler<MessageEvent> for Ban {
+//!     on_command_args!(
+//!         MessageEvent,
+//!         Command::new("ban")
+//!             .alias("禁言")
+//!             .arg("user", ArgType::At)
+//!             .optional_arg("minutes", ArgType::Int)
+//!     );
+//!     async fn handle(&self, event: MessageEvent, matcher: Matcher<MessageEvent>) {
+//!         let args = event.get_command_args().unwrap();
+//!         let user = args.get_at("user").unwrap();
+//!         let minutes = args.get_i64("minutes").unwrap_or(10);
+//!     }
+//! }
+//! ```
+//!
+//! 多轮会话，会话状态按 (bot, user, group) 保存：
+//!
+//! ```ignore
+//! async fn handle(&self, event: MessageEvent, matcher: Matcher<MessageEvent>) {
+//!     let city = match matcher.got("city", Some("要查询哪个城市？")).await {
+//!         Some(city) => city,
+//!         None => return,
+//!     };
+//!     if city == "取消" {
+//!         // finish 不会中断 handle，需立即 return
+//!         return matcher.finish(Some("已取消")).await;
+//!     }
+//!     let times = matcher.state().get::<i64>("times").unwrap_or(0);
+//!     matcher.state().set("times", times + 1);
+//!     matcher.finish(Some("查询完成")).await;
+//! }
+//! ```
+//!
 //! 使用 Onebot Api：
 //!
 //! ```rust
@@ -111,6 +168,12 @@
 //! let msg:Option<nonebot_rs::api_resp::Message> = bot.get_msg().await
 //! ```
 //!
+//! 需要区分超时、断连与调用失败？使用 `try_` 前缀版本：
+//!
+//! ```ignore
+//! let msg:Result<nonebot_rs::api_resp::Message, nonebot_rs::ApiError> = matcher.try_get_msg().await
+//! ```
+//!
 //! 就是这么简单~
 //!
 //! ## 定时任务
@@ -162,6 +225,23 @@
 //! }
 //! ```
 //!
+//! 优雅关闭
+//!
+//! ```ignore
+//! use nonebot_rs;
+//!
+//! fn main() {
+//!     let nb = nonebot_rs::Nonebot::new();
+//!     // 也可以通过 nb.shutdown_handle() 在其他位置关闭
+//!     nb.run_until(async {
+//!         tokio::signal::ctrl_c().await.ok();
+//!     })
+//! }
+//! ```
+//!
+//! 关闭时 nbrs 将断开所有连接，为每个 Bot 发出 `BotDisconnect` 事件，
+//! 并调用所有 Plugin 的 `on_shutdown`（最长等待 `nb.shutdown_timeout`）。
+//!
 //! enjoy~
 
 /////////////////////////////////////////////////////////////////////////////////
@@ -174,6 +254,8 @@ pub mod api_resp;
 mod bot;
 /// 内建组件
 pub mod builtin;
+/// 声明式命令解析
+pub mod command;
 #[doc(hidden)]
 pub mod comms;
 /// nbrs 设置项
@@ -196,21 +278,24 @@ pub mod plugin;
 #[cfg(feature = "scheduler")]
 #[cfg_attr(docsrs, doc(cfg(feature = "scheduler")))]
 pub mod scheduler;
+/// 持久化键值存储
+pub mod storage;
 mod utils;
 
 use std::collections::HashMap;
-use tokio::sync::{broadcast, mpsc, watch};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, mpsc, oneshot, watch};
 
 #[doc(inline)]
 pub use action::Action;
 #[doc(inline)]
-pub use api_resp::{ApiResp, RespData};
+pub use api_resp::{ApiError, ApiResp, RespData};
 pub use async_trait::async_trait;
 #[doc(inline)]
-pub use bot::Bot;
+pub use bot::{Bot, ForwardResult};
 #[doc(inline)]
 #[doc(inline)]
-pub use message::Message;
+pub use message::{ForwardBuilder, Message, MessageChain};
 #[doc(inline)]
 pub use plugin::Plugin;
 
@@ -224,8 +309,8 @@ pub use matcher::matchers::Matchers;
 
 /// Onebot Api mpsc channel Bot 发送 WebSocket 接收
 pub type ApiSender = mpsc::Sender<ApiChannelItem>;
-/// Bot 监视 Onebot ApiResp Watch channel
-pub type ApiRespWatcher = watch::Receiver<ApiResp>;
+/// Bot 等待 Onebot ApiResp 的请求表，以 echo 为键，每个连接独立
+pub type ApiRespPending = Arc<Mutex<HashMap<String, oneshot::Sender<Result<ApiResp, ApiError>>>>>;
 /// Event broadcast channel sender 所有 WebSocket Plugin 共享，
 /// WebSocket 发送，Plugin 接收
 pub type EventSender = broadcast::Sender<event::Event>;
@@ -240,6 +325,8 @@ pub type ActionReceiver = mpsc::Receiver<Action>;
 pub type BotSender = watch::Sender<HashMap<String, Bot>>;
 /// 接收广播的所有可用 Bot
 pub type BotGetter = watch::Receiver<HashMap<String, Bot>>;
+/// Nonebot 关闭信号 Receiver，值变为 true 时各连接应当关闭
+pub type ShutdownReceiver = watch::Receiver<bool>;
 /// nbrs 本体
 ///
 /// 用于注册 `Matcher`，暂存配置项，以及启动实例
@@ -260,6 +347,29 @@ pub struct Nonebot {
     pub bot_getter: BotGetter,
     /// event handler
     plugins: HashMap<String, Box<dyn Plugin + Send + Sync>>,
+    /// 关闭信号 Sender
+    shutdown_sender: Arc<watch::Sender<bool>>,
+    /// 关闭信号 Receiver
+    shutdown_receiver: ShutdownReceiver,
+    /// 关闭时等待所有 Plugin `on_shutdown` 的时限
+    pub shutdown_timeout: std::time::Duration,
+    /// 数据存储后端，默认为 `global.data_dir` 下的 `FileStorage`
+    pub storage: storage::SharedStorage,
+}
+
+/// Nonebot 关闭句柄
+///
+/// 调用 `shutdown` 后 Nonebot 将关闭所有连接、通知所有 Plugin 并退出运行
+#[derive(Debug, Clone)]
+pub struct ShutdownHandle {
+    sender: Arc<watch::Sender<bool>>,
+}
+
+impl ShutdownHandle {
+    /// 通知 Nonebot 关闭
+    pub fn shutdown(&self) {
+        self.sender.send(true).ok();
+    }
 }
 
 /// api channel 传递项
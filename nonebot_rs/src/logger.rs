@@ -51,10 +51,11 @@ pub struct Logger;
 
 impl Logger {
     async fn event_recv(self, mut event_receiver: crate::EventReceiver) {
-        while let Ok(event) = event_receiver.recv().await {
+        while let Some(event) = crate::plugin::recv_event(&mut event_receiver).await {
             match &event {
                 Event::Message(m) => message_logger(m),
                 Event::Meta(m) => meta_logger(m),
+                Event::Unknown(v) => event!(Level::INFO, "Receive unknown event {}", v),
                 _ => {}
             }
         }
@@ -0,0 +1,297 @@
+use crate::event::{MessageEvent, MetaEvent, NoticeEvent, RequestEvent};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+
+/// 持久化 Matcher 开关使用的存储 namespace
+pub static SWITCH_NAMESPACE: &str = "matchers";
+
+/// 按群组与用户控制 Matcher 所需的事件来源
+pub trait AclEvent {
+    /// 事件来源用户
+    fn acl_user(&self) -> Option<Cow<'_, str>>;
+    /// 事件来源群组
+    fn acl_group(&self) -> Option<Cow<'_, str>>;
+}
+
+impl AclEvent for MessageEvent {
+    fn acl_user(&self) -> Option<Cow<'_, str>> {
+        match self {
+            MessageEvent::Private(p) => Some(p.user_id.as_str().into()),
+            MessageEvent::Group(g) => Some(g.user_id.as_str().into()),
+        }
+    }
+
+    fn acl_group(&self) -> Option<Cow<'_, str>> {
+        match self {
+            MessageEvent::Private(_) => None,
+            MessageEvent::Group(g) => Some(g.group_id.as_str().into()),
+        }
+    }
+}
+
+impl AclEvent for NoticeEvent {
+    fn acl_user(&self) -> Option<Cow<'_, str>> {
+        self.notice.user_id()
+    }
+
+    fn acl_group(&self) -> Option<Cow<'_, str>> {
+        self.notice.group_id()
+    }
+}
+
+impl AclEvent for RequestEvent {
+    fn acl_user(&self) -> Option<Cow<'_, str>> {
+        self.request.user_id()
+    }
+
+    fn acl_group(&self) -> Option<Cow<'_, str>> {
+        self.request.group_id()
+    }
+}
+
+impl AclEvent for MetaEvent {
+    fn acl_user(&self) -> Option<Cow<'_, str>> {
+        None
+    }
+
+    fn acl_group(&self) -> Option<Cow<'_, str>> {
+        None
+    }
+}
+
+/// Matcher 开关作用范围
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SwitchScope {
+    /// 全局
+    Global,
+    /// 指定群组
+    Group(String),
+    /// 指定用户
+    User(String),
+}
+
+/// Matcher 的群组与用户开关
+///
+/// 可在 Nonebotrs.toml 中按 Matcher 设置，群组与用户 ID 可为字符串或整数
+///
+/// ```toml
+/// [matcher.echo]
+/// allowed_groups = [123456]   # 仅在这些群组中响应（缺省不限制）
+/// allowed_users = ["654321"]  # 仅响应这些用户（缺省不限制）
+/// disabled_groups = [111111]  # 不在这些群组中响应
+/// disabled_users = [222222]   # 不响应这些用户
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Acl {
+    /// 允许的群组，None 为不限制
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_groups: Option<HashSet<String>>,
+    /// 允许的用户，None 为不限制
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_users: Option<HashSet<String>>,
+    /// 禁用的群组
+    #[serde(default)]
+    pub disabled_groups: HashSet<String>,
+    /// 禁用的用户
+    #[serde(default)]
+    pub disabled_users: HashSet<String>,
+}
+
+fn id_set(value: &toml::Value) -> Option<HashSet<String>> {
+    value
+        .as_array()?
+        .iter()
+        .map(|id| match id {
+            toml::Value::String(s) => Some(s.clone()),
+            toml::Value::Integer(i) => Some(i.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn allowed(
+    id: Option<&str>,
+    allowed: &Option<HashSet<String>>,
+    disabled: &HashSet<String>,
+) -> bool {
+    match id {
+        Some(id) => !disabled.contains(id) && !matches!(allowed, Some(set) if !set.contains(id)),
+        None => true,
+    }
+}
+
+impl Acl {
+    /// 从 `[matcher.<name>]` 配置读取，忽略格式错误的项
+    pub fn from_config(config: &HashMap<String, toml::Value>) -> Self {
+        let get = |key: &str| config.get(key).and_then(id_set);
+        Acl {
+            allowed_groups: get("allowed_groups"),
+            allowed_users: get("allowed_users"),
+            disabled_groups: get("disabled_groups").unwrap_or_default(),
+            disabled_users: get("disabled_users").unwrap_or_default(),
+        }
+    }
+
+    /// 事件是否允许触发 Matcher
+    ///
+    /// 私聊消息不受群组规则限制，无来源的事件总是允许
+    pub fn allows<E>(&self, event: &E) -> bool
+    where
+        E: AclEvent,
+    {
+        allowed(
+            event.acl_group().as_deref(),
+            &self.allowed_groups,
+            &self.disabled_groups,
+        ) && allowed(
+            event.acl_user().as_deref(),
+            &self.allowed_users,
+            &self.disabled_users,
+        )
+    }
+
+    /// 在指定群组或用户启用或禁用，忽略 `SwitchScope::Global`
+    ///
+    /// 启用时若存在允许列表则同时加入
+    pub fn set(&mut self, scope: &SwitchScope, disable: bool) {
+        let (allowed, disabled, id) = match scope {
+            SwitchScope::Global => return,
+            SwitchScope::Group(group) => {
+                (&mut self.allowed_groups, &mut self.disabled_groups, group)
+            }
+            SwitchScope::User(user) => (&mut self.allowed_users, &mut self.disabled_users, user),
+        };
+        if disable {
+            disabled.insert(id.to_string());
+            if let Some(allowed) = allowed {
+                allowed.remove(id);
+            }
+        } else {
+            disabled.remove(id);
+            if let Some(allowed) = allowed {
+                allowed.insert(id.to_string());
+            }
+        }
+    }
+}
+
+/// 运行时修改的 Matcher 开关，持久化后于 Bot 连接时恢复
+///
+/// 只记录运行时的修改，恢复时叠加在配置文件的设置之上
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MatcherSwitch {
+    /// 全局开关，None 为未修改
+    #[serde(default)]
+    pub disable: Option<bool>,
+    /// 运行时禁用的群组
+    #[serde(default)]
+    pub disabled_groups: HashSet<String>,
+    /// 运行时禁用的用户
+    #[serde(default)]
+    pub disabled_users: HashSet<String>,
+    /// 运行时启用的群组
+    #[serde(default)]
+    pub enabled_groups: HashSet<String>,
+    /// 运行时启用的用户
+    #[serde(default)]
+    pub enabled_users: HashSet<String>,
+}
+
+impl MatcherSwitch {
+    /// 在指定范围启用或禁用
+    pub fn set(&mut self, scope: &SwitchScope, disable: bool) {
+        let (disabled, enabled, id) = match scope {
+            SwitchScope::Global => {
+                self.disable = Some(disable);
+                return;
+            }
+            SwitchScope::Group(group) => {
+                (&mut self.disabled_groups, &mut self.enabled_groups, group)
+            }
+            SwitchScope::User(user) => (&mut self.disabled_users, &mut self.enabled_users, user),
+        };
+        if disable {
+            enabled.remove(id);
+            disabled.insert(id.to_string());
+        } else {
+            disabled.remove(id);
+            enabled.insert(id.to_string());
+        }
+    }
+
+    /// 将运行时修改叠加到 acl
+    pub fn apply(&self, acl: &mut Acl) {
+        for group in &self.disabled_groups {
+            acl.set(&SwitchScope::Group(group.clone()), true);
+        }
+        for user in &self.disabled_users {
+            acl.set(&SwitchScope::User(user.clone()), true);
+        }
+        for group in &self.enabled_groups {
+            acl.set(&SwitchScope::Group(group.clone()), false);
+        }
+        for user in &self.enabled_users {
+            acl.set(&SwitchScope::User(user.clone()), false);
+        }
+    }
+}
+
+#[test]
+fn matcher_switch_test() {
+    let event: MessageEvent = serde_json::from_str(
+        r#"{"message_type":"group","time":0,"self_id":1,"sub_type":"normal","message_id":1,"group_id":10,"user_id":2,"anonymous":null,"message":"hi","raw_message":"hi","font":0,"sender":{"user_id":2,"nickname":"","card":"","sex":"unknown","age":0,"area":"","level":"","role":"member","title":""}}"#,
+    )
+    .unwrap();
+    let mut switch = MatcherSwitch::default();
+    let acl = |switch: &MatcherSwitch, config: Acl| {
+        let mut acl = config;
+        switch.apply(&mut acl);
+        acl
+    };
+    assert!(acl(&switch, Acl::default()).allows(&event));
+    switch.set(&SwitchScope::Group("10".to_string()), true);
+    assert!(!acl(&switch, Acl::default()).allows(&event));
+    switch.set(&SwitchScope::Group("10".to_string()), false);
+    switch.set(&SwitchScope::User("2".to_string()), true);
+    assert!(!acl(&switch, Acl::default()).allows(&event));
+    assert_eq!(switch.disable, None);
+
+    // 配置文件后续的修改依然生效
+    let config = Acl {
+        disabled_groups: ["20".to_string()].iter().cloned().collect(),
+        ..Acl::default()
+    };
+    let merged = acl(&switch, config);
+    assert!(merged.disabled_groups.contains("20"));
+    assert!(merged.disabled_users.contains("2"));
+    assert!(!merged.disabled_groups.contains("10"));
+}
+
+#[test]
+fn acl_config_test() {
+    let config: HashMap<String, toml::Value> = toml::from_str(
+        r#"
+        allowed_groups = [10, "20"]
+        disabled_users = [2]
+        max_times = 3
+        "#,
+    )
+    .unwrap();
+    let mut acl = Acl::from_config(&config);
+    assert_eq!(acl.allowed_users, None);
+    let event = |group_id: &str, user_id: &str| -> MessageEvent {
+        serde_json::from_str(&format!(
+            r#"{{"message_type":"group","time":0,"self_id":1,"sub_type":"normal","message_id":1,"group_id":{},"user_id":{},"anonymous":null,"message":"hi","raw_message":"hi","font":0,"sender":{{"user_id":{},"nickname":"","card":"","sex":"unknown","age":0,"area":"","level":"","role":"member","title":""}}}}"#,
+            group_id, user_id, user_id
+        ))
+        .unwrap()
+    };
+    assert!(acl.allows(&event("20", "3")));
+    assert!(!acl.allows(&event("30", "3")));
+    assert!(!acl.allows(&event("10", "2")));
+    acl.set(&SwitchScope::Group("30".to_string()), false);
+    assert!(acl.allows(&event("30", "3")));
+    acl.set(&SwitchScope::Group("10".to_string()), true);
+    assert!(!acl.allows(&event("10", "3")));
+}
@@ -9,6 +9,23 @@ pub enum MatchersAction {
     },
     /// 移除 Matcher
     RemoveMatcher { matcher_name: String },
+    /// 在指定范围启用或禁用 Matcher
+    SetMatcherDisable {
+        matcher_name: String,
+        scope: super::acl::SwitchScope,
+        disable: bool,
+    },
+    /// 替换 Matcher 的群组与用户开关
+    SetMatcherAcl {
+        matcher_name: String,
+        acl: super::acl::Acl,
+    },
+    /// 移除临时 Matcher，matcher_name 为 None 时移除所有临时 Matcher
+    RemoveTempMatchers { matcher_name: Option<String> },
+    /// 获取所有 Matcher 信息
+    ListMatchers {
+        sender: tokio::sync::mpsc::Sender<Vec<super::matchers::MatcherInfo>>,
+    },
 }
 
 impl super::matchers::Matchers {
@@ -33,6 +50,35 @@ impl super::matchers::Matchers {
                 );
                 self.remove_matcher(&matcher_name);
             }
+            MatchersAction::SetMatcherDisable {
+                matcher_name,
+                scope,
+                disable,
+            } => {
+                event!(
+                    Level::INFO,
+                    "Set Matcher {} disable:{} in {:?}",
+                    matcher_name.blue(),
+                    disable,
+                    scope
+                );
+                self.set_matcher_switch(&matcher_name, &scope, disable);
+            }
+            MatchersAction::SetMatcherAcl { matcher_name, acl } => {
+                event!(
+                    Level::INFO,
+                    "Set Matcher {} acl {:?}",
+                    matcher_name.blue(),
+                    acl
+                );
+                self.set_matcher_acl(&matcher_name, &acl);
+            }
+            MatchersAction::RemoveTempMatchers { matcher_name } => {
+                self.remove_temp_matchers(matcher_name.as_deref());
+            }
+            MatchersAction::ListMatchers { sender } => {
+                sender.try_send(self.matcher_infos()).ok();
+            }
         }
     }
 }
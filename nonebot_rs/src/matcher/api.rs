@@ -1,24 +1,11 @@
 use super::Matcher;
 use crate::api_resp;
 use crate::event::SelfId;
+use crate::ApiError;
 use colored::*;
 use tracing::{event, Level};
 
 macro_rules! no_resp_api {
-    ($fn_name: ident, $param: ident: $param_type: ty) => {
-        pub async fn $fn_name(&self, $param: $param_type) {
-            if let Some(bot) = &self.bot {
-                bot.$fn_name($param).await
-            } else {
-                event!(
-                    Level::ERROR,
-                    "Calling api {} {}",
-                    stringify!($fn_name).blue(),
-                    "with unbuilt matcher!".red()
-                );
-            }
-        }
-    };
     ($fn_name: ident, $($param: ident: $param_type: ty),*) => {
         pub async fn $fn_name(&self, $($param: $param_type,)*) {
             if let Some(bot) = &self.bot {
@@ -32,41 +19,21 @@ macro_rules! no_resp_api {
                 );
             }
         }
-    };
-}
 
-macro_rules! resp_api {
-    ($fn_name: ident, $resp_data_type: ty) => {
-        pub async fn $fn_name(&self) -> Option<$resp_data_type> {
-            if let Some(bot) = &self.bot {
-                bot.$fn_name().await
-            } else {
-                event!(
-                    Level::ERROR,
-                    "Calling api {} {}",
-                    stringify!($fn_name).blue(),
-                    "with unbuilt matcher!".red()
-                );
-                None
-            }
-        }
-    };
-    ($fn_name: ident, $resp_data_type: ty, $param: ident: $param_type: ty) => {
-        pub async fn $fn_name(&self, $param: $param_type) -> Option<$resp_data_type> {
-            if let Some(bot) = &self.bot {
-                bot.$fn_name($param).await
-            } else {
-                event!(
-                    Level::ERROR,
-                    "Calling api {} {}",
-                    stringify!($fn_name).blue(),
-                    "with unbuilt matcher!".red()
-                );
-                None
+        paste::paste! {
+            /// 等待 Onebot 返回，调用失败返回对应 ApiError
+            pub async fn [<try_ $fn_name>](&self, $($param: $param_type,)*) -> Result<(), ApiError> {
+                match &self.bot {
+                    Some(bot) => bot.[<try_ $fn_name>]($($param,)*).await,
+                    None => Err(ApiError::UnbuiltMatcher),
+                }
             }
         }
     };
-    ($fn_name: ident, $resp_data_type: ty, $($param: ident: $param_type: ty),*) => {
+}
+
+macro_rules! resp_api {
+    ($fn_name: ident, $resp_data_type: ty $(, $param: ident: $param_type: ty)*) => {
         pub async fn $fn_name(&self, $($param: $param_type,)*) -> Option<$resp_data_type> {
             if let Some(bot) = &self.bot {
                 bot.$fn_name($($param,)*).await
@@ -80,6 +47,16 @@ macro_rules! resp_api {
                 None
             }
         }
+
+        paste::paste! {
+            /// 调用失败返回对应 ApiError
+            pub async fn [<try_ $fn_name>](&self, $($param: $param_type,)*) -> Result<$resp_data_type, ApiError> {
+                match &self.bot {
+                    Some(bot) => bot.[<try_ $fn_name>]($($param,)*).await,
+                    None => Err(ApiError::UnbuiltMatcher),
+                }
+            }
+        }
     };
 }
 
@@ -100,6 +77,49 @@ where
         }
     }
 
+    /// 请求 Onebot Api，不等待 Onebot 返回（Bot 已断开返回 Err）
+    pub async fn try_call_api(&self, api: crate::api::Api) -> Result<(), ApiError> {
+        match &self.bot {
+            Some(bot) => bot.try_call_api(api).await,
+            None => Err(ApiError::UnbuiltMatcher),
+        }
+    }
+
+    /// 请求 Onebot Api，等待 Onebot 返回项（调用失败返回对应 ApiError）
+    pub async fn try_call_api_resp(
+        &self,
+        api: crate::api::Api,
+    ) -> Result<crate::api_resp::ApiResp, ApiError> {
+        match &self.bot {
+            Some(bot) => bot.try_call_api_resp(api).await,
+            None => Err(ApiError::UnbuiltMatcher),
+        }
+    }
+
+    /// 请求未定义的 Onebot Api，返回原始 data 字段
+    pub async fn call_raw(
+        &self,
+        action: &str,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value, ApiError> {
+        match &self.bot {
+            Some(bot) => bot.call_raw(action, params).await,
+            None => Err(ApiError::UnbuiltMatcher),
+        }
+    }
+
+    /// 请求未定义的 Onebot Api，按类型序列化参数并解析 data 字段
+    pub async fn call<P, R>(&self, action: &str, params: P) -> Result<R, ApiError>
+    where
+        P: serde::Serialize,
+        R: serde::de::DeserializeOwned,
+    {
+        match &self.bot {
+            Some(bot) => bot.call(action, params).await,
+            None => Err(ApiError::UnbuiltMatcher),
+        }
+    }
+
     /// 请求 Onebot Api，等待 Onebot 返回项（30s 后 timeout 返回 None）
     pub async fn call_api_resp(&self, api: crate::api::Api) -> Option<crate::api_resp::ApiResp> {
         if let Some(bot) = &self.bot {
@@ -184,6 +204,21 @@ where
         reason: String
     );
     no_resp_api!(set_restart, delay: i64);
+    no_resp_api!(
+        upload_group_file,
+        group_id: String,
+        file: String,
+        name: String,
+        folder: Option<String>
+    );
+    no_resp_api!(set_essence_msg, message_id: i32);
+    no_resp_api!(mark_msg_as_read, message_id: i32);
+    no_resp_api!(
+        set_group_portrait,
+        group_id: String,
+        file: String,
+        cache: i32
+    );
 
     resp_api!(
         send_msg,
@@ -238,6 +273,41 @@ where
     resp_api!(can_send_image, api_resp::SendCheck);
     resp_api!(get_status, crate::event::Status);
     resp_api!(get_version_info, api_resp::VersionInfo);
+    resp_api!(
+        send_group_forward_msg,
+        api_resp::MessageId,
+        group_id: String,
+        messages: Vec<crate::Message>
+    );
+    resp_api!(
+        send_private_forward_msg,
+        api_resp::MessageId,
+        user_id: String,
+        messages: Vec<crate::Message>
+    );
+    resp_api!(
+        get_group_msg_history,
+        api_resp::MessageHistory,
+        message_seq: Option<i64>,
+        group_id: String
+    );
+    resp_api!(
+        get_group_file_system_info,
+        api_resp::GroupFileSystemInfo,
+        group_id: String
+    );
+    resp_api!(
+        get_essence_msg_list,
+        Vec<api_resp::EssenceMsg>,
+        group_id: String
+    );
+    resp_api!(ocr_image, api_resp::OcrImage, image: String);
+    resp_api!(
+        get_group_at_all_remain,
+        api_resp::GroupAtAllRemain,
+        group_id: String
+    );
+    resp_api!(get_online_clients, api_resp::OnlineClients, no_cache: bool);
 
     // pub async fn get_friend_list(&self) -> Option<Vec<crate::api_resp::FriendListItem>> {
     //     if let Some(bot) = &self.bot {
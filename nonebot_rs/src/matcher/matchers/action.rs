@@ -1,5 +1,7 @@
+use super::MatcherInfo;
 use super::{Matchers, MatchersBTreeMap, MatchersHashMap};
 use crate::event::{MessageEvent, MetaEvent, NoticeEvent, RequestEvent};
+use crate::matcher::acl::{Acl, MatcherSwitch, SwitchScope};
 use crate::matcher::{action::MatchersAction, Matcher};
 use std::collections::{BTreeMap, HashMap};
 use tokio::sync::broadcast;
@@ -21,6 +23,7 @@ impl Matchers {
             bot_getter: None,
             action_sender: sender,
             config: HashMap::new(),
+            running: Default::default(),
         }
     }
 
@@ -89,6 +92,29 @@ impl Matchers {
         f(&self.meta, &self.config).await;
     }
 
+    /// 从 `[matcher.<name>]` 配置读取群组与用户开关
+    pub fn load_all_matcher_acl(&mut self) {
+        fn f<E>(
+            matcherb: &mut MatchersBTreeMap<E>,
+            config: &HashMap<String, HashMap<String, toml::Value>>,
+        ) where
+            E: Clone,
+        {
+            for (_, matcherh) in matcherb.iter_mut() {
+                for (matcher_name, matcher) in matcherh.iter_mut() {
+                    if let Some(data) = config.get(&matcher_name.to_lowercase()) {
+                        matcher.set_acl(Acl::from_config(data));
+                    }
+                }
+            }
+        }
+
+        f(&mut self.message, &self.config);
+        f(&mut self.notice, &self.config);
+        f(&mut self.request, &self.config);
+        f(&mut self.meta, &self.config);
+    }
+
     #[doc(hidden)]
     fn add_matcher<E>(
         matcherb: &mut MatchersBTreeMap<E>,
@@ -179,6 +205,120 @@ impl Matchers {
         disable_matcher_(&mut self.request, name, disable);
         disable_matcher_(&mut self.meta, name, disable);
     }
+
+    /// 根据 Matcher.name 在指定范围启用或禁用 Matcher
+    pub fn set_matcher_switch(&mut self, name: &str, scope: &SwitchScope, disable: bool) {
+        fn set_matcher_switch_<E>(
+            matcherb: &mut MatchersBTreeMap<E>,
+            name: &str,
+            scope: &SwitchScope,
+            disable: bool,
+        ) where
+            E: Clone,
+        {
+            for (_, matcherh) in matcherb.iter_mut() {
+                if let Some(matcher) = matcherh.get_mut(name) {
+                    matcher.set_switch(scope, disable);
+                }
+            }
+        }
+
+        set_matcher_switch_(&mut self.message, name, scope, disable);
+        set_matcher_switch_(&mut self.notice, name, scope, disable);
+        set_matcher_switch_(&mut self.request, name, scope, disable);
+        set_matcher_switch_(&mut self.meta, name, scope, disable);
+    }
+
+    /// 根据 Matcher.name 替换群组与用户开关
+    pub fn set_matcher_acl(&mut self, name: &str, acl: &Acl) {
+        fn set_matcher_acl_<E>(matcherb: &mut MatchersBTreeMap<E>, name: &str, acl: &Acl)
+        where
+            E: Clone,
+        {
+            for (_, matcherh) in matcherb.iter_mut() {
+                if let Some(matcher) = matcherh.get_mut(name) {
+                    matcher.set_acl(acl.clone());
+                }
+            }
+        }
+
+        set_matcher_acl_(&mut self.message, name, acl);
+        set_matcher_acl_(&mut self.notice, name, acl);
+        set_matcher_acl_(&mut self.request, name, acl);
+        set_matcher_acl_(&mut self.meta, name, acl);
+    }
+
+    /// 根据 Matcher.name 应用持久化的 Matcher 开关
+    pub fn apply_matcher_switch(&mut self, name: &str, switch: &MatcherSwitch) {
+        fn apply_matcher_switch_<E>(
+            matcherb: &mut MatchersBTreeMap<E>,
+            name: &str,
+            switch: &MatcherSwitch,
+        ) where
+            E: Clone,
+        {
+            for (_, matcherh) in matcherb.iter_mut() {
+                if let Some(matcher) = matcherh.get_mut(name) {
+                    matcher.apply_switch(switch);
+                }
+            }
+        }
+
+        apply_matcher_switch_(&mut self.message, name, switch);
+        apply_matcher_switch_(&mut self.notice, name, switch);
+        apply_matcher_switch_(&mut self.request, name, switch);
+        apply_matcher_switch_(&mut self.meta, name, switch);
+    }
+
+    /// 移除临时 Matcher，name 为 None 时移除所有临时 Matcher
+    pub fn remove_temp_matchers(&mut self, name: Option<&str>) {
+        fn remove_temp_matchers_<E>(matcherb: &mut MatchersBTreeMap<E>, name: Option<&str>)
+        where
+            E: Clone,
+        {
+            for (_, matcherh) in matcherb.iter_mut() {
+                matcherh.retain(|n, m| !m.is_temp() || matches!(name, Some(name) if name != n));
+            }
+        }
+
+        remove_temp_matchers_(&mut self.message, name);
+        remove_temp_matchers_(&mut self.notice, name);
+        remove_temp_matchers_(&mut self.request, name);
+        remove_temp_matchers_(&mut self.meta, name);
+    }
+
+    /// 获取所有 Matcher 信息
+    pub fn matcher_infos(&self) -> Vec<MatcherInfo> {
+        fn matcher_infos_<E>(
+            matcherb: &MatchersBTreeMap<E>,
+            event_type: &'static str,
+            infos: &mut Vec<MatcherInfo>,
+        ) where
+            E: Clone,
+        {
+            for (_, matcherh) in matcherb {
+                for (_, matcher) in matcherh {
+                    infos.push(MatcherInfo {
+                        name: matcher.name.clone(),
+                        event_type,
+                        priority: matcher.priority,
+                        block: matcher.block,
+                        temp: matcher.temp,
+                        timeout: matcher.timeout,
+                        disable: matcher.disable,
+                        acl: matcher.acl.clone(),
+                    });
+                }
+            }
+        }
+
+        let mut infos = vec![];
+        matcher_infos_(&self.message, "message", &mut infos);
+        matcher_infos_(&self.notice, "notice", &mut infos);
+        matcher_infos_(&self.request, "request", &mut infos);
+        matcher_infos_(&self.meta, "meta", &mut infos);
+        infos
+    }
 }
 
 #[doc(hidden)]
@@ -1,4 +1,6 @@
+use crate::command::CommandEvent;
 use crate::event::{Event, MessageEvent, MetaEvent, NoticeEvent, RequestEvent, SelfId};
+use crate::matcher::acl::{AclEvent, MatcherSwitch, SWITCH_NAMESPACE};
 use crate::matcher::Matcher;
 use async_trait::async_trait;
 use colored::*;
@@ -17,6 +19,20 @@ pub type ActionSender = broadcast::Sender<super::action::MatchersAction>;
 
 pub const PLUGIN_NAME: &'static str = "Matcher";
 
+/// Matcher 信息
+#[derive(Debug, Clone)]
+pub struct MatcherInfo {
+    pub name: String,
+    /// message|notice|request|meta
+    pub event_type: &'static str,
+    pub priority: i8,
+    pub block: bool,
+    pub temp: bool,
+    pub timeout: Option<i64>,
+    pub disable: bool,
+    pub acl: crate::matcher::acl::Acl,
+}
+
 /// 根据 `Event` 类型分类存储对应的 `Matcher`
 #[derive(Clone, Debug)]
 pub struct Matchers {
@@ -34,6 +50,8 @@ pub struct Matchers {
     action_sender: ActionSender,
     /// Config
     config: HashMap<String, HashMap<String, toml::Value>>,
+    /// 处理中的 handler 持有读锁，关闭时获取写锁以等待其完成
+    pub(crate) running: std::sync::Arc<tokio::sync::RwLock<()>>,
 }
 
 impl Matchers {
@@ -55,13 +73,16 @@ impl Matchers {
             }
             Event::Nonebot(e) => match e {
                 crate::event::NbEvent::BotConnect { bot } => {
+                    self.load_switches(&bot).await;
                     log_load_matchers(&self);
                     self.run_on_connect(bot, false).await;
                 }
                 crate::event::NbEvent::BotDisconnect { bot } => {
                     self.run_on_connect(bot, true).await;
                 }
+                _ => {}
             },
+            Event::Unknown(_) => {}
         }
     }
 
@@ -72,7 +93,7 @@ impl Matchers {
         event: E,
         bot: crate::bot::Bot,
     ) where
-        E: Clone + Send + 'static + std::fmt::Debug + SelfId,
+        E: Clone + Send + 'static + std::fmt::Debug + SelfId + CommandEvent + AclEvent,
     {
         event!(Level::TRACE, "handling event {:?}", event);
         // 根据不同 Event 类型，逐级匹配，判定是否 Block
@@ -94,13 +115,17 @@ impl Matchers {
         bot: crate::bot::Bot,
     ) -> bool
     where
-        E: Clone + Send + 'static + std::fmt::Debug + SelfId,
+        E: Clone + Send + 'static + std::fmt::Debug + SelfId + CommandEvent + AclEvent,
     {
         event!(Level::TRACE, "handling event_ {:?}", e);
         // 每级 Matcher 匹配，返回是否 block
         let mut get_block = false;
         let config = bot.config.clone();
         for (name, matcher) in matcherh.iter_mut() {
+            // 群组与用户开关先于 Matcher 匹配判定
+            if !matcher.acl.allows(&e) {
+                continue;
+            }
             let matched = matcher
                 .build(bot.clone())
                 .match_(e.clone(), config.clone(), self)
@@ -119,18 +144,39 @@ impl Matchers {
         get_block
     }
 
-    async fn event_recv(mut self, mut event_receiver: crate::EventReceiver) {
-        let mut receiver = self.action_sender.subscribe();
-        while let Ok(event) = event_receiver.recv().await {
-
-            match receiver.try_recv() {
-                Ok(action) => self.handle_action(action),
-                Err(_) => {}
+    /// 恢复持久化的 Matcher 开关
+    async fn load_switches(&mut self, bot: &crate::bot::Bot) {
+        match bot
+            .storage(SWITCH_NAMESPACE)
+            .scan::<MatcherSwitch>("")
+            .await
+        {
+            Ok(switches) => {
+                for (name, switch) in switches {
+                    self.apply_matcher_switch(&name, &switch);
+                }
             }
+            Err(e) => event!(Level::WARN, "Load Matcher switches failed: {}", e),
+        }
+    }
 
-            let bots = self.bot_getter.clone().unwrap().borrow().clone();
-            if let Some(bot) = bots.get(&event.get_self_id()) {
-                self.handle_events(event, bot).await;
+    async fn event_recv(mut self, mut event_receiver: crate::EventReceiver) {
+        let mut receiver = self.action_sender.subscribe();
+        loop {
+            tokio::select! {
+                // 优先处理 Action，保证事件匹配前 Matcher 已更新
+                biased;
+                Ok(action) = receiver.recv() => self.handle_action(action),
+                event = crate::plugin::recv_event(&mut event_receiver) => {
+                    let event = match event {
+                        Some(event) => event,
+                        None => break,
+                    };
+                    let bots = self.bot_getter.clone().unwrap().borrow().clone();
+                    if let Some(bot) = bots.get(&event.get_self_id()) {
+                        self.handle_events(event, bot).await;
+                    }
+                }
             }
         }
     }
@@ -153,8 +199,14 @@ impl crate::Plugin for Matchers {
             config.try_into().expect("Matchers get error config");
         self.config = config;
         self.load_all_matcher_config().await;
+        self.load_all_matcher_acl();
         event!(Level::INFO, "Loaded Matchers config: {:?}", self.config);
     }
+
+    async fn on_shutdown(&mut self) {
+        // 等待所有处理中的 handler 完成
+        let _ = self.running.write().await;
+    }
 }
 
 fn log_load_matchers(matchers: &crate::Matchers) {
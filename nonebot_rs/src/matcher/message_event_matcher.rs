@@ -1,4 +1,7 @@
-use super::{build_temp_message_event_matcher, Handler, Matcher};
+use super::prompt::Prompt;
+use super::{
+    build_scoped_temp_message_event_matcher, build_temp_message_event_matcher, Handler, Matcher,
+};
 use crate::event::MessageEvent;
 use crate::ApiChannelItem;
 use async_trait::async_trait;
@@ -14,6 +17,35 @@ impl Matcher<MessageEvent> {
         self.send(vec![msg]).await;
     }
 
+    /// 发送字符串格式消息，auto_escape 为 false 时解析其中的 CQ 码
+    pub async fn send_raw(&self, msg: &str, auto_escape: bool) {
+        self.send(crate::message::from_string(msg, auto_escape))
+            .await;
+    }
+
+    /// 向当前会话发送合并转发，超出限制时拆分为多条依次发送
+    ///
+    /// 某条发送失败时返回已发送的消息 ID 与错误
+    pub async fn send_forward(
+        &self,
+        forward: crate::message::ForwardBuilder,
+    ) -> crate::ForwardResult {
+        match (&self.bot, &self.event) {
+            (Some(bot), Some(MessageEvent::Private(p))) => {
+                bot.send_private_forward(&p.user_id, forward).await
+            }
+            (Some(bot), Some(MessageEvent::Group(g))) => {
+                bot.send_group_forward(&g.group_id, forward).await
+            }
+            _ => Err((vec![], crate::ApiError::UnbuiltMatcher)),
+        }
+    }
+
+    /// 获取 `on_regex!` 匹配的捕获组
+    pub fn get_captures(&self) -> Option<&crate::builtin::matches::Captures> {
+        self.event.as_ref()?.get_captures()
+    }
+
     /// 设置临时 Matcher<MessageEvent>
     pub async fn set_temp_message_event_matcher<H>(&self, event: &MessageEvent, handler: H)
     where
@@ -42,6 +74,30 @@ impl Matcher<MessageEvent> {
             }
         }
 
+        let event = self.request_event(msg).await?;
+        let msg = crate::utils::remove_space(event.get_raw_message());
+        if msg.is_empty() {
+            None
+        } else {
+            Some(msg)
+        }
+    }
+
+    /// 等待当前会话的下一条消息事件，超时返回 None
+    ///
+    /// 传入 msg 为发送给用户的请求文本信息（传入 None 表示不向用户发送请求信息）
+    pub async fn request_event(&self, msg: Option<&str>) -> Option<MessageEvent> {
+        let prompt = match msg {
+            Some(msg) => Prompt::new(msg),
+            None => Prompt::default(),
+        };
+        self.prompt(prompt).await
+    }
+
+    /// 按 `Prompt` 选项等待消息事件，返回完整的 MessageEvent
+    ///
+    /// 校验失败时发送重试提示并继续等待，超时返回 None
+    pub async fn prompt(&self, prompt: Prompt) -> Option<MessageEvent> {
         /// 临时 Matcher 的 Handler struct
         struct Temp;
 
@@ -56,72 +112,136 @@ impl Matcher<MessageEvent> {
                     .api_sender
                     .send(ApiChannelItem::MessageEvent(event))
                     .await
-                    .unwrap();
+                    .ok();
             }
 
             // timeout 后调用，通知接受端 Timeout
             fn timeout_drop(&self, matcher: &Matcher<MessageEvent>) {
                 let sender = matcher.bot.clone().unwrap().api_sender;
-                tokio::spawn(async move { sender.send(ApiChannelItem::TimeOut).await.unwrap() });
+                tokio::spawn(async move { sender.send(ApiChannelItem::TimeOut).await.ok() });
             }
         }
 
-        // 搭建临时通道接受 MessageEvent
-        let (sender, mut receiver) = tokio::sync::mpsc::channel::<ApiChannelItem>(4);
-        let event = self.event.clone().unwrap();
-        // 根据提供的 event Handler 构建仅指向当先通话的 Temp Matcher
-        let mut m = build_temp_message_event_matcher(&event, Temp);
-        // 使用临时通道构建专用 Bot
-        let bot = crate::bot::Bot::new(
-            "Temp".to_string(),
-            crate::config::BotConfig::default(),
-            sender,
-            self.bot.clone().unwrap().action_sender.clone(),
-            self.bot.clone().unwrap().api_resp_watcher.clone(),
-        );
-        // 绑定专用 Bot
-        m.bot = Some(bot);
-        self.set_message_matcher(m).await;
-
-        // Temp Matcher 已就绪，发送提示信息
-        if let Some(msg) = msg {
-            self.send_text(msg).await;
-        }
+        let event = self.event.clone()?;
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(prompt.timeout);
+        let mut message = prompt.message.clone();
+        loop {
+            // 搭建临时通道接受 MessageEvent
+            let (sender, mut receiver) = tokio::sync::mpsc::channel::<ApiChannelItem>(4);
+            // 根据提供的 event Handler 构建仅指向当先通话的 Temp Matcher
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            let mut m = build_scoped_temp_message_event_matcher(
+                &event,
+                Temp,
+                remaining.as_secs() as i64 + 1,
+                prompt.scope,
+            );
+            // 使用临时通道构建专用 Bot
+            let bot = crate::bot::Bot::new(
+                "Temp".to_string(),
+                crate::config::BotConfig::default(),
+                sender,
+                self.bot.clone().unwrap().action_sender.clone(),
+                self.bot.clone().unwrap().api_resp_pending.clone(),
+                self.bot.clone().unwrap().storage.clone(),
+            );
+            // 绑定专用 Bot
+            m.bot = Some(bot);
+            let name = m.name.clone();
+            self.set_message_matcher(m).await;
 
-        // 等待接收 MessageEvent
-        while let Some(data) = receiver.recv().await {
-            match data {
-                ApiChannelItem::MessageEvent(event) => {
-                    let msg = crate::utils::remove_space(event.get_raw_message());
-                    if msg.is_empty() {
-                        return None;
-                    } else {
-                        return Some(msg);
-                    }
-                }
-                ApiChannelItem::TimeOut => {
+            // Temp Matcher 已就绪，发送提示信息
+            if let Some(message) = message.take() {
+                self.send(message).await;
+            }
+
+            // 等待接收 MessageEvent
+            let event = match tokio::time::timeout_at(deadline, receiver.recv()).await {
+                Ok(Some(ApiChannelItem::MessageEvent(event))) => event,
+                Ok(Some(ApiChannelItem::TimeOut)) | Ok(None) => {
                     event!(Level::DEBUG, "Temp Matcher TimeOut");
                     return None;
                 }
-                // 中转 temp Matcher 的 Remove Action
-                // ApiChannelItem::Action(action) => self.set(action).await,
-                _ => {
+                Ok(Some(_)) => {
                     event!(
                         Level::WARN,
                         "{}",
                         "Temp Matcher接受端接收到错误Api或Action消息".bright_red()
                     );
-                } // 忽视 event 该 receiver 永不应该收到 event
+                    return None;
+                }
+                Err(_) => {
+                    event!(Level::DEBUG, "Temp Matcher TimeOut");
+                    self.remove_matcher(&name).await;
+                    return None;
+                }
+            };
+            if prompt.validate(&event) {
+                return Some(event);
             }
+            message = prompt.retry_message.clone();
+        }
+    }
+
+    /// 当前会话的状态
+    ///
+    /// 以 (bot, user, group) 区分会话，同一会话内多次触发 Matcher 共享状态，
+    /// 超过 `set_session_timeout` 设置的时间未访问后过期
+    pub fn state(&self) -> super::state::State {
+        match &self.event {
+            Some(event) => self.sessions.get(&event.into()),
+            None => super::state::State::default(),
+        }
+    }
+
+    /// 获取会话状态中 key 对应的文本，不存在时发送 prompt 并等待用户输入
+    ///
+    /// 输入将存入会话状态，超时或输入为空返回 None
+    pub async fn got(&self, key: &str, prompt: Option<&str>) -> Option<String> {
+        let state = self.state();
+        if let Some(value) = state.get::<String>(key) {
+            return Some(value);
         }
+        let value = self.request_message(None, prompt).await?;
+        state.set(key, value.clone());
+        Some(value)
+    }
+
+    /// 拒绝 key 当前的值，发送 prompt 并重新等待用户输入
+    ///
+    /// 返回 None 时会话已超时，调用方需立即 `return` 结束 handle
+    pub async fn reject(&self, key: &str, prompt: Option<&str>) -> Option<String> {
+        self.state().remove(key);
+        self.got(key, prompt).await
+    }
+
+    /// 发送 prompt 并暂停至用户发送下一条消息
+    ///
+    /// 返回 None 时会话已超时，调用方需立即 `return` 结束 handle
+    pub async fn pause(&self, prompt: Option<&str>) -> Option<MessageEvent> {
+        self.request_event(prompt).await
+    }
 
-        None
+    /// 结束当前会话并清除会话状态，msg 不为 None 时发送该消息
+    ///
+    /// 不会中断 handle，调用后需立即 `return`，否则后续代码仍会执行
+    pub async fn finish(&self, msg: Option<&str>) {
+        if let Some(event) = &self.event {
+            self.sessions.remove(&event.into());
+        }
+        if let Some(msg) = msg {
+            self.send_text(msg).await;
+        }
     }
 
-    /// 发送 Vec<Message> 消息
-    pub async fn send(&self, msg: Vec<crate::message::Message>) {
+    /// 发送消息，可传入 `MessageChain`、`Vec<Message>`、`Message` 或 `&str`
+    pub async fn send<M>(&self, msg: M)
+    where
+        M: Into<crate::message::MessageChain>,
+    {
         if let (Some(bot), Some(event)) = (&self.bot, &self.event) {
-            bot.send_by_message_event(&event, msg).await;
+            bot.send_by_message_event(event, msg.into().into_vec())
+                .await;
         } else {
             event!(
                 Level::ERROR,
@@ -1,5 +1,7 @@
+use crate::command::CommandEvent;
 use crate::config::BotConfig;
 use crate::event::{MessageEvent, SelfId};
+use crate::message::Message;
 use crate::utils::timestamp;
 use crate::Action;
 use async_trait::async_trait;
@@ -7,6 +9,8 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+/// Matcher 群组与用户开关
+pub mod acl;
 mod action;
 #[doc(hidden)]
 pub mod api;
@@ -16,8 +20,16 @@ pub mod matchers;
 pub mod message_event_matcher;
 /// Preludo for Matcher
 pub mod prelude;
+/// 等待用户输入选项
+pub mod prompt;
+/// 限流
+pub mod rate_limit;
+#[doc(hidden)]
+pub mod request_event_matcher;
 #[doc(hidden)]
 pub mod set_get;
+/// 会话状态
+pub mod state;
 
 /// rule 函数类型
 pub type Rule<E> = Arc<dyn Fn(&E, &BotConfig) -> bool + Send + Sync>;
@@ -50,10 +62,16 @@ where
     handler: Arc<RwLock<dyn Handler<E> + Sync + Send>>,
     /// 是否被禁用
     pub disable: bool,
+    /// 群组与用户开关
+    pub acl: acl::Acl,
     /// 是否为临时 Matcher
     pub temp: bool,
     /// 过期时间戳
     pub timeout: Option<i64>,
+    /// 限流
+    pub rate_limit: Option<rate_limit::RateLimit>,
+    /// 会话状态存储
+    sessions: state::SessionStore,
 
     #[doc(hidden)]
     event: Option<E>,
@@ -135,8 +153,11 @@ where
             block: true,
             handler: Arc::new(RwLock::new(handler)),
             disable: false,
+            acl: acl::Acl::default(),
             temp: false,
             timeout: None,
+            rate_limit: None,
+            sessions: state::SessionStore::default(),
 
             event: None,
         }
@@ -173,7 +194,7 @@ where
         matchers: &mut matchers::Matchers,
     ) -> bool
     where
-        E: Send + 'static + SelfId,
+        E: Send + 'static + SelfId + CommandEvent + acl::AclEvent,
     {
         // Matcher 处理流程，匹配成功返回 true 并行处理 handler
         let mut event = event.clone();
@@ -201,11 +222,33 @@ where
             if !handler.match_(&mut event) {
                 return false;
             }
+            // 命令解析失败，回复用法后不再调用 handle
+            if let Some(api) = event.take_command_error() {
+                if let Some(bot) = &self.bot {
+                    bot.call_api(api).await;
+                }
+                return true;
+            }
+            // 仅对匹配成功的事件计数，被限流时回复冷却提示
+            if let Some(rate_limit) = &self.rate_limit {
+                if let Err(limited) = rate_limit.check(&event) {
+                    let reply = rate_limit
+                        .cooldown_reply(limited.cooldown)
+                        .filter(|_| limited.notify)
+                        .and_then(|reply| event.reply_api(vec![Message::text(reply)]));
+                    if let (Some(bot), Some(api)) = (&self.bot, reply) {
+                        bot.call_api(api).await;
+                    }
+                    return true;
+                }
+            }
             let matcher = self.clone().set_event(&event);
             let handler = self.handler.clone();
+            let running = matchers.running.clone().read_owned().await;
             tokio::spawn(async move {
                 let handler = handler.read().await;
-                handler.handle(event, matcher).await
+                handler.handle(event, matcher).await;
+                drop(running);
             });
         }
         return true;
@@ -218,6 +261,67 @@ where
         }
     }
 
+    /// 获取当前 Matcher 的存储句柄，namespace 为 `matcher.{name}`
+    pub fn storage(&self) -> Option<crate::storage::Namespace> {
+        self.bot
+            .as_ref()
+            .map(|bot| bot.storage(&format!("matcher.{}", self.name)))
+    }
+
+    /// 在指定范围启用或禁用 Matcher
+    pub async fn set_matcher_disable(&self, name: &str, scope: acl::SwitchScope, disable: bool) {
+        self.send_matchers_action(action::MatchersAction::SetMatcherDisable {
+            matcher_name: name.to_string(),
+            scope,
+            disable,
+        });
+    }
+
+    /// 替换 Matcher 的群组与用户开关
+    pub async fn set_matcher_acl(&self, name: &str, acl: acl::Acl) {
+        self.send_matchers_action(action::MatchersAction::SetMatcherAcl {
+            matcher_name: name.to_string(),
+            acl,
+        });
+    }
+
+    /// 移除临时 Matcher，name 为 None 时移除所有临时 Matcher
+    pub async fn remove_temp_matchers(&self, name: Option<&str>) {
+        self.send_matchers_action(action::MatchersAction::RemoveTempMatchers {
+            matcher_name: name.map(|name| name.to_string()),
+        });
+    }
+
+    /// 获取所有已加载 Matcher 的信息
+    pub async fn list_matchers(&self) -> Vec<matchers::MatcherInfo> {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(1);
+        self.send_matchers_action(action::MatchersAction::ListMatchers { sender });
+        match tokio::time::timeout(std::time::Duration::from_secs(5), receiver.recv()).await {
+            Ok(Some(infos)) => infos,
+            _ => vec![],
+        }
+    }
+
+    fn send_matchers_action(&self, action: action::MatchersAction) {
+        if let Some(action_sender) = &self.action_sender {
+            action_sender.send(action).ok();
+        } else {
+            tracing::event!(tracing::Level::WARN, "Action Sender not init.")
+        }
+    }
+
+    /// 从 Matchers 移除 Matcher
+    pub async fn remove_matcher(&self, name: &str) {
+        let action = action::MatchersAction::RemoveMatcher {
+            matcher_name: name.to_string(),
+        };
+        if let Some(action_sender) = &self.action_sender {
+            action_sender.send(action).unwrap();
+        } else {
+            tracing::event!(tracing::Level::WARN, "Action Sender not init.")
+        }
+    }
+
     /// 向 Matchers 添加 Matcher<MessageEvent>
     pub async fn set_message_matcher(&self, matcher: Matcher<MessageEvent>) {
         let action = action::MatchersAction::AddMessageEventMatcher {
@@ -239,24 +343,57 @@ pub fn build_temp_message_event_matcher<H>(
 where
     H: Handler<MessageEvent> + Send + Sync + 'static,
 {
-    use crate::event::UserId;
-    let mut m = Matcher::new(
-        &format!(
-            "{}-{}-{}",
-            event.get_self_id(),
-            event.get_user_id(),
-            event.get_time()
-        ),
+    build_scoped_temp_message_event_matcher(
+        event,
         handler,
+        prompt::DEFAULT_PROMPT_TIMEOUT as i64,
+        prompt::PromptScope::User,
     )
-    .add_rule(crate::builtin::rules::is_user(event.get_user_id()))
+}
+
+/// 构建指定 timeout（秒）与范围的临时 Matcher<MessageEvent>
+pub fn build_scoped_temp_message_event_matcher<H>(
+    event: &MessageEvent,
+    handler: H,
+    timeout: i64,
+    scope: prompt::PromptScope,
+) -> Matcher<MessageEvent>
+where
+    H: Handler<MessageEvent> + Send + Sync + 'static,
+{
+    use crate::event::UserId;
+    let group_id = match event {
+        MessageEvent::Group(g) => Some(g.group_id.clone()),
+        MessageEvent::Private(_) => None,
+    };
+    let mut m = match (&group_id, scope) {
+        (Some(group_id), prompt::PromptScope::Group) => Matcher::new(
+            &format!(
+                "{}-group-{}-{}",
+                event.get_self_id(),
+                group_id,
+                event.get_time()
+            ),
+            handler,
+        ),
+        _ => Matcher::new(
+            &format!(
+                "{}-{}-{}",
+                event.get_self_id(),
+                event.get_user_id(),
+                event.get_time()
+            ),
+            handler,
+        )
+        .add_rule(crate::builtin::rules::is_user(event.get_user_id())),
+    }
     .add_rule(crate::builtin::rules::is_bot(event.get_self_id()));
-    if let MessageEvent::Group(g) = event {
-        m.add_rule(crate::builtin::rules::in_group(g.group_id.clone()));
+    if let Some(group_id) = group_id {
+        m.add_rule(crate::builtin::rules::in_group(group_id));
     } else {
         m.add_rule(crate::builtin::rules::is_private_message_event());
     }
     m.set_priority(0)
         .set_temp(true)
-        .set_timeout(timestamp() + 30)
+        .set_timeout(timestamp() + timeout)
 }
@@ -1,7 +1,13 @@
+pub use super::prompt::{Prompt, PromptScope};
+pub use super::rate_limit::{LimitScope, RateLimit};
 pub use super::{Handler, Matcher};
 pub use crate::async_trait;
 pub use crate::builtin::*;
-pub use crate::event::{Event, MessageEvent, SelfId, UserId};
-pub use crate::message::Message;
-pub use crate::{on_command, on_match_all, on_start_with};
+pub use crate::command::{ArgType, ArgValue, Command, CommandArgs};
+pub use crate::event::{Event, MessageEvent, NoticeEvent, RequestEvent, SelfId, UserId};
+pub use crate::message::{ForwardBuilder, Message, MessageChain};
+pub use crate::{
+    on_command, on_command_args, on_endswith, on_fullmatch, on_keyword, on_match_all, on_regex,
+    on_start_with,
+};
 pub use serde_json::Value;
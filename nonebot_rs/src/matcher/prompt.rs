@@ -0,0 +1,110 @@
+use crate::event::MessageEvent;
+use crate::message::MessageChain;
+use std::sync::Arc;
+
+/// 默认等待时间（秒）
+pub const DEFAULT_PROMPT_TIMEOUT: u64 = 30;
+
+/// 等待消息的范围
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PromptScope {
+    /// 仅接受当前用户的消息
+    User,
+    /// 接受当前群内任意用户的消息，私聊时同 User
+    Group,
+}
+
+/// 输入校验函数类型
+pub type Validator = Arc<dyn Fn(&MessageEvent) -> bool + Send + Sync>;
+
+/// `Matcher::prompt` 选项
+///
+/// ```ignore
+/// let event = matcher
+///     .prompt(
+///         Prompt::new("请发送一张图片")
+///             .timeout(60)
+///             .validator(|e| !e.get_message().images().is_empty(), "这不是图片，请重新发送"),
+///     )
+///     .await;
+/// ```
+#[derive(Clone)]
+pub struct Prompt {
+    pub(crate) message: Option<MessageChain>,
+    pub(crate) timeout: u64,
+    pub(crate) validator: Option<Validator>,
+    pub(crate) retry_message: Option<MessageChain>,
+    pub(crate) scope: PromptScope,
+}
+
+impl Default for Prompt {
+    fn default() -> Self {
+        Prompt {
+            message: None,
+            timeout: DEFAULT_PROMPT_TIMEOUT,
+            validator: None,
+            retry_message: None,
+            scope: PromptScope::User,
+        }
+    }
+}
+
+impl std::fmt::Debug for Prompt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Prompt")
+            .field("message", &self.message)
+            .field("timeout", &self.timeout)
+            .field("validator", &self.validator.is_some())
+            .field("retry_message", &self.retry_message)
+            .field("scope", &self.scope)
+            .finish()
+    }
+}
+
+impl Prompt {
+    /// 新建 Prompt，等待前发送 message
+    pub fn new<M>(message: M) -> Self
+    where
+        M: Into<MessageChain>,
+    {
+        Prompt {
+            message: Some(message.into()),
+            ..Default::default()
+        }
+    }
+
+    /// 设置等待时间（秒），包含校验失败后重新等待的时间
+    pub fn timeout(mut self, timeout: u64) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 设置校验函数，校验失败时发送 retry_message 并重新等待
+    pub fn validator<F, M>(mut self, validator: F, retry_message: M) -> Self
+    where
+        F: Fn(&MessageEvent) -> bool + Send + Sync + 'static,
+        M: Into<MessageChain>,
+    {
+        self.validator = Some(Arc::new(validator));
+        self.retry_message = Some(retry_message.into());
+        self
+    }
+
+    /// 设置等待范围
+    pub fn scope(mut self, scope: PromptScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    /// 接受当前群内任意用户的消息
+    pub fn group(self) -> Self {
+        self.scope(PromptScope::Group)
+    }
+
+    pub(crate) fn validate(&self, event: &MessageEvent) -> bool {
+        match &self.validator {
+            Some(validator) => validator(event),
+            None => true,
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use super::acl::AclEvent;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 超过该数量时清理已恢复的计数
+const PRUNE_THRESHOLD: usize = 1024;
+
+/// 限流计数范围
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LimitScope {
+    /// 每个用户单独计数
+    User,
+    /// 每个群组单独计数，私聊按用户计数
+    Group,
+    /// 所有事件共同计数
+    Matcher,
+}
+
+/// 限流算法
+///
+/// 次数与时长为 0 时按 1 处理
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Algorithm {
+    /// 令牌桶，容量 capacity，每 period 补满
+    TokenBucket { capacity: u32, period: Duration },
+    /// 固定窗口，每 window 内最多 limit 次
+    FixedWindow { limit: u32, window: Duration },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    /// 令牌桶剩余令牌或固定窗口已用次数
+    value: f64,
+    /// 令牌桶上次补充时间或固定窗口开始时间
+    since: Instant,
+    /// 本次冷却期内是否已提示
+    notified: bool,
+}
+
+/// 被限流时的冷却信息
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limited {
+    /// 剩余冷却时间
+    pub cooldown: Duration,
+    /// 是否为本次冷却期内首次被限流，仅首次需要回复冷却提示
+    pub notify: bool,
+}
+
+/// Matcher 限流
+///
+/// clone 后共享计数，可在多个 Matcher 间共用
+///
+/// ```ignore
+/// matcher.set_rate_limit(
+///     RateLimit::fixed_window(3, 60)
+///         .scope(LimitScope::User)
+///         .cooldown_message("查询过于频繁，请 {cooldown} 秒后再试"),
+/// )
+/// ```
+#[derive(Debug, Clone)]
+pub struct RateLimit {
+    algorithm: Algorithm,
+    scope: LimitScope,
+    cooldown_message: Option<String>,
+    buckets: Arc<Mutex<HashMap<String, Bucket>>>,
+}
+
+impl RateLimit {
+    pub fn new(algorithm: Algorithm) -> Self {
+        let algorithm = match algorithm {
+            Algorithm::TokenBucket { capacity, period } => Algorithm::TokenBucket {
+                capacity: capacity.max(1),
+                period: period.max(Duration::from_secs(1)),
+            },
+            Algorithm::FixedWindow { limit, window } => Algorithm::FixedWindow {
+                limit: limit.max(1),
+                window: window.max(Duration::from_secs(1)),
+            },
+        };
+        RateLimit {
+            algorithm,
+            scope: LimitScope::User,
+            cooldown_message: None,
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// 令牌桶，每 secs 秒最多 capacity 次，可短时突发
+    pub fn token_bucket(capacity: u32, secs: u64) -> Self {
+        RateLimit::new(Algorithm::TokenBucket {
+            capacity,
+            period: Duration::from_secs(secs),
+        })
+    }
+
+    /// 固定窗口，每 secs 秒最多 limit 次
+    pub fn fixed_window(limit: u32, secs: u64) -> Self {
+        RateLimit::new(Algorithm::FixedWindow {
+            limit,
+            window: Duration::from_secs(secs),
+        })
+    }
+
+    /// 设置计数范围，默认 `LimitScope::User`
+    pub fn scope(mut self, scope: LimitScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    /// 设置被限流时的回复，`{cooldown}` 替换为剩余秒数
+    ///
+    /// 每个计数范围在一次冷却期内只回复一次
+    pub fn cooldown_message(mut self, message: &str) -> Self {
+        self.cooldown_message = Some(message.to_string());
+        self
+    }
+
+    /// 被限流时的回复文本
+    pub fn cooldown_reply(&self, cooldown: Duration) -> Option<String> {
+        let secs = cooldown.as_secs() + u64::from(cooldown.subsec_nanos() > 0);
+        self.cooldown_message
+            .as_ref()
+            .map(|message| message.replace("{cooldown}", &secs.to_string()))
+    }
+
+    fn key<E>(&self, event: &E) -> String
+    where
+        E: AclEvent,
+    {
+        let user = || format!("user:{}", event.acl_user().unwrap_or_default());
+        match self.scope {
+            LimitScope::User => user(),
+            LimitScope::Group => match event.acl_group() {
+                Some(group) => format!("group:{}", group),
+                None => user(),
+            },
+            LimitScope::Matcher => String::new(),
+        }
+    }
+
+    /// 计数一次，被限流时返回冷却信息
+    pub fn check<E>(&self, event: &E) -> Result<(), Limited>
+    where
+        E: AclEvent,
+    {
+        self.check_at(&self.key(event), Instant::now())
+    }
+
+    fn check_at(&self, key: &str, now: Instant) -> Result<(), Limited> {
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() > PRUNE_THRESHOLD {
+            let algorithm = self.algorithm;
+            buckets.retain(|_, bucket| !recovered(&algorithm, bucket, now));
+        }
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+            value: match self.algorithm {
+                Algorithm::TokenBucket { capacity, .. } => capacity as f64,
+                Algorithm::FixedWindow { .. } => 0.0,
+            },
+            since: now,
+            notified: false,
+        });
+        let cooldown = match self.algorithm {
+            Algorithm::TokenBucket { capacity, period } => {
+                let rate = capacity as f64 / period.as_secs_f64();
+                let elapsed = now.saturating_duration_since(bucket.since).as_secs_f64();
+                bucket.value = (bucket.value + elapsed * rate).min(capacity as f64);
+                bucket.since = now;
+                if bucket.value >= 1.0 {
+                    bucket.value -= 1.0;
+                    None
+                } else {
+                    Some(Duration::from_secs_f64((1.0 - bucket.value) / rate))
+                }
+            }
+            Algorithm::FixedWindow { limit, window } => {
+                let elapsed = now.saturating_duration_since(bucket.since);
+                if elapsed >= window {
+                    bucket.value = 0.0;
+                    bucket.since = now;
+                }
+                if bucket.value < limit as f64 {
+                    bucket.value += 1.0;
+                    None
+                } else {
+                    Some(window - now.saturating_duration_since(bucket.since))
+                }
+            }
+        };
+        match cooldown {
+            None => {
+                bucket.notified = false;
+                Ok(())
+            }
+            Some(cooldown) => {
+                let notify = !bucket.notified;
+                bucket.notified = true;
+                Err(Limited { cooldown, notify })
+            }
+        }
+    }
+}
+
+/// 计数是否已恢复到初始状态
+fn recovered(algorithm: &Algorithm, bucket: &Bucket, now: Instant) -> bool {
+    let elapsed = now.saturating_duration_since(bucket.since);
+    match algorithm {
+        Algorithm::TokenBucket { period, .. } => elapsed >= *period,
+        Algorithm::FixedWindow { window, .. } => elapsed >= *window,
+    }
+}
+
+#[test]
+fn rate_limit_test() {
+    let now = Instant::now();
+    let limit = RateLimit::fixed_window(2, 10);
+    assert!(limit.check_at("a", now).is_ok());
+    assert!(limit.check_at("a", now).is_ok());
+    let limited = |cooldown, notify| Err(Limited { cooldown, notify });
+    assert_eq!(
+        limit.check_at("a", now),
+        limited(Duration::from_secs(10), true)
+    );
+    assert_eq!(
+        limit.check_at("a", now + Duration::from_secs(1)),
+        limited(Duration::from_secs(9), false)
+    );
+    assert!(limit.check_at("b", now).is_ok());
+    assert!(limit.check_at("a", now + Duration::from_secs(10)).is_ok());
+
+    let limit = RateLimit::token_bucket(2, 10).cooldown_message("{cooldown}s");
+    assert!(limit.check_at("a", now).is_ok());
+    assert!(limit.check_at("a", now).is_ok());
+    let limited = limit.check_at("a", now).unwrap_err();
+    assert_eq!(
+        limit.cooldown_reply(limited.cooldown),
+        Some("5s".to_string())
+    );
+    assert!(limit.check_at("a", now + Duration::from_secs(5)).is_ok());
+    assert!(
+        limit
+            .check_at("a", now + Duration::from_secs(5))
+            .unwrap_err()
+            .notify
+    );
+
+    // 0 按 1 处理，不会 panic
+    let limit = RateLimit::token_bucket(0, 0);
+    assert!(limit.check_at("a", now).is_ok());
+    assert_eq!(
+        limit.check_at("a", now).unwrap_err().cooldown,
+        Duration::from_secs(1)
+    );
+}
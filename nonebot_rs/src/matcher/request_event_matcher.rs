@@ -0,0 +1,21 @@
+use super::Matcher;
+use crate::event::RequestEvent;
+use crate::ApiError;
+
+impl Matcher<RequestEvent> {
+    /// 同意当前请求，remark 为好友备注，加群请求忽略
+    pub async fn approve(&self, remark: &str) -> Result<(), ApiError> {
+        match (&self.bot, &self.event) {
+            (Some(bot), Some(event)) => bot.approve(event, remark).await,
+            _ => Err(ApiError::UnbuiltMatcher),
+        }
+    }
+
+    /// 拒绝当前请求，reason 为拒绝理由，加好友请求忽略
+    pub async fn reject(&self, reason: &str) -> Result<(), ApiError> {
+        match (&self.bot, &self.event) {
+            (Some(bot), Some(event)) => bot.reject(event, reason).await,
+            _ => Err(ApiError::UnbuiltMatcher),
+        }
+    }
+}
@@ -94,4 +94,40 @@ where
         self.timeout = Some(timeout);
         self.clone()
     }
+
+    /// 应用持久化的 Matcher 开关
+    pub fn apply_switch(&mut self, switch: &super::acl::MatcherSwitch) -> Matcher<E> {
+        if let Some(disable) = switch.disable {
+            self.disable = disable;
+        }
+        switch.apply(&mut self.acl);
+        self.clone()
+    }
+
+    /// 设置限流，在 Handler 匹配成功后计数
+    pub fn set_rate_limit(&mut self, rate_limit: super::rate_limit::RateLimit) -> Matcher<E> {
+        self.rate_limit = Some(rate_limit);
+        self.clone()
+    }
+
+    /// 设置群组与用户开关
+    pub fn set_acl(&mut self, acl: super::acl::Acl) -> Matcher<E> {
+        self.acl = acl;
+        self.clone()
+    }
+
+    /// 在指定范围启用或禁用
+    pub fn set_switch(&mut self, scope: &super::acl::SwitchScope, disable: bool) -> Matcher<E> {
+        match scope {
+            super::acl::SwitchScope::Global => self.disable = disable,
+            _ => self.acl.set(scope, disable),
+        }
+        self.clone()
+    }
+
+    /// 设置会话状态在最后一次访问后的存活时间（秒）
+    pub fn set_session_timeout(&mut self, timeout: i64) -> Matcher<E> {
+        self.sessions.timeout = timeout;
+        self.clone()
+    }
 }
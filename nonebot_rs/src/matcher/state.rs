@@ -0,0 +1,155 @@
+use crate::event::{MessageEvent, SelfId, UserId};
+use crate::utils::timestamp;
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// 会话默认过期时间（秒）
+pub const DEFAULT_SESSION_TIMEOUT: i64 = 300;
+
+/// 会话标识 (bot, user, group)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SessionKey {
+    pub bot_id: String,
+    pub user_id: String,
+    /// 私聊为 None
+    pub group_id: Option<String>,
+}
+
+impl From<&MessageEvent> for SessionKey {
+    fn from(event: &MessageEvent) -> Self {
+        SessionKey {
+            bot_id: event.get_self_id(),
+            user_id: event.get_user_id(),
+            group_id: match event {
+                MessageEvent::Private(_) => None,
+                MessageEvent::Group(g) => Some(g.group_id.clone()),
+            },
+        }
+    }
+}
+
+/// 会话状态，按 key 存取任意类型的值
+///
+/// clone 后指向同一会话
+#[derive(Clone, Default)]
+pub struct State {
+    values: Arc<Mutex<HashMap<String, Box<dyn Any + Send + Sync>>>>,
+}
+
+impl std::fmt::Debug for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let values = self.values.lock().unwrap();
+        f.debug_struct("State")
+            .field("keys", &values.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl State {
+    /// 获取值，不存在或类型不符时返回 None
+    pub fn get<T>(&self, key: &str) -> Option<T>
+    where
+        T: Clone + 'static,
+    {
+        self.values
+            .lock()
+            .unwrap()
+            .get(key)?
+            .downcast_ref()
+            .cloned()
+    }
+
+    /// 设置值
+    pub fn set<T>(&self, key: &str, value: T)
+    where
+        T: Send + Sync + 'static,
+    {
+        self.values
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), Box::new(value));
+    }
+
+    /// 是否存在该 key
+    pub fn contains(&self, key: &str) -> bool {
+        self.values.lock().unwrap().contains_key(key)
+    }
+
+    /// 移除值
+    pub fn remove(&self, key: &str) {
+        self.values.lock().unwrap().remove(key);
+    }
+
+    /// 清空状态
+    pub fn clear(&self) {
+        self.values.lock().unwrap().clear();
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Session {
+    state: State,
+    /// 过期时间戳
+    expires: i64,
+}
+
+/// Matcher 的会话状态存储
+#[derive(Debug, Clone)]
+pub struct SessionStore {
+    sessions: Arc<Mutex<HashMap<SessionKey, Session>>>,
+    /// 会话在最后一次访问后的存活时间（秒）
+    pub timeout: i64,
+}
+
+impl Default for SessionStore {
+    fn default() -> Self {
+        SessionStore {
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+            timeout: DEFAULT_SESSION_TIMEOUT,
+        }
+    }
+}
+
+impl SessionStore {
+    /// 获取会话状态，不存在或已过期时新建，并刷新过期时间
+    pub fn get(&self, key: &SessionKey) -> State {
+        let now = timestamp();
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.retain(|_, session| session.expires > now);
+        let session = sessions.entry(key.clone()).or_insert_with(|| Session {
+            state: State::default(),
+            expires: 0,
+        });
+        session.expires = now + self.timeout;
+        session.state.clone()
+    }
+
+    /// 结束会话
+    pub fn remove(&self, key: &SessionKey) {
+        self.sessions.lock().unwrap().remove(key);
+    }
+}
+
+#[test]
+fn session_store_test() {
+    let key = SessionKey {
+        bot_id: "1".to_string(),
+        user_id: "2".to_string(),
+        group_id: None,
+    };
+    let store = SessionStore::default();
+    store.get(&key).set("count", 1i64);
+    assert_eq!(store.get(&key).get::<i64>("count"), Some(1));
+    assert_eq!(store.get(&key).get::<String>("count"), None);
+
+    store.remove(&key);
+    assert!(!store.get(&key).contains("count"));
+
+    let store = SessionStore {
+        timeout: -1,
+        ..Default::default()
+    };
+    store.get(&key).set("count", 1i64);
+    assert!(!store.get(&key).contains("count"));
+}
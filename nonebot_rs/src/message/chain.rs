@@ -0,0 +1,214 @@
+use super::Message;
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, AddAssign, Deref, DerefMut};
+
+/// 消息链，`Vec<Message>` 的包装
+///
+/// 反序列化时同时支持 array 与 CQ 码 string 格式，`Display` 输出 CQ 码字符串
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(transparent)]
+pub struct MessageChain(pub Vec<Message>);
+
+impl MessageChain {
+    /// 新建空消息链
+    pub fn new() -> Self {
+        MessageChain(vec![])
+    }
+
+    /// 追加纯文本
+    pub fn text(mut self, text: &str) -> Self {
+        self.0.push(Message::text(text.to_string()));
+        self
+    }
+
+    /// 追加 @某人
+    pub fn at(mut self, user_id: &str) -> Self {
+        self.0.push(Message::at(user_id.to_string()));
+        self
+    }
+
+    /// 追加回复
+    pub fn reply(mut self, message_id: &str) -> Self {
+        self.0.push(Message::reply(message_id.to_string()));
+        self
+    }
+
+    /// 追加图片
+    pub fn image(mut self, file: &str) -> Self {
+        self.0.push(Message::image(
+            file.to_string(),
+            None,
+            None,
+            None,
+            None,
+            None,
+        ));
+        self
+    }
+
+    /// 追加任意消息段
+    pub fn segment(mut self, message: Message) -> Self {
+        self.0.push(message);
+        self
+    }
+
+    /// 提取所有纯文本
+    pub fn extract_plain_text(&self) -> String {
+        self.0
+            .iter()
+            .filter_map(|m| match m {
+                Message::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// 所有被 @ 的 ID（@全体成员为 `all`）
+    pub fn mentions(&self) -> Vec<&str> {
+        self.0
+            .iter()
+            .filter_map(|m| match m {
+                Message::At { qq } => Some(qq.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// 回复的消息 ID
+    pub fn reply_id(&self) -> Option<&str> {
+        self.0.iter().find_map(|m| match m {
+            Message::Reply { id } => Some(id.as_str()),
+            _ => None,
+        })
+    }
+
+    /// 所有图片，存在 URL 时返回 URL 否则返回文件名
+    pub fn images(&self) -> Vec<&str> {
+        self.0
+            .iter()
+            .filter_map(|m| match m {
+                Message::Image { file, url, .. } => Some(url.as_deref().unwrap_or(file)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// 转为 `Vec<Message>`
+    pub fn into_vec(self) -> Vec<Message> {
+        self.0
+    }
+}
+
+impl Deref for MessageChain {
+    type Target = Vec<Message>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for MessageChain {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl std::fmt::Display for MessageChain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", super::to_cq_string(&self.0))
+    }
+}
+
+impl<'de> Deserialize<'de> for MessageChain {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        super::message_deserializer(d).map(MessageChain)
+    }
+}
+
+impl From<Vec<Message>> for MessageChain {
+    fn from(messages: Vec<Message>) -> Self {
+        MessageChain(messages)
+    }
+}
+
+impl From<MessageChain> for Vec<Message> {
+    fn from(chain: MessageChain) -> Self {
+        chain.0
+    }
+}
+
+impl From<Message> for MessageChain {
+    fn from(message: Message) -> Self {
+        MessageChain(vec![message])
+    }
+}
+
+impl From<&str> for MessageChain {
+    fn from(text: &str) -> Self {
+        MessageChain::new().text(text)
+    }
+}
+
+impl From<String> for MessageChain {
+    fn from(text: String) -> Self {
+        MessageChain(vec![Message::text(text)])
+    }
+}
+
+impl IntoIterator for MessageChain {
+    type Item = Message;
+    type IntoIter = std::vec::IntoIter<Message>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a MessageChain {
+    type Item = &'a Message;
+    type IntoIter = std::slice::Iter<'a, Message>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<T> Add<T> for MessageChain
+where
+    T: Into<MessageChain>,
+{
+    type Output = MessageChain;
+
+    fn add(mut self, rhs: T) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl<T> AddAssign<T> for MessageChain
+where
+    T: Into<MessageChain>,
+{
+    fn add_assign(&mut self, rhs: T) {
+        self.0.extend(rhs.into().0);
+    }
+}
+
+#[test]
+fn message_chain_test() {
+    let chain =
+        MessageChain::new().reply("1").at("123") + " hello " + Message::at("all".to_string());
+    let mut chain = chain + "world";
+    chain += MessageChain::new().image("a.jpg");
+    assert_eq!(chain.extract_plain_text(), " hello world");
+    assert_eq!(chain.mentions(), vec!["123", "all"]);
+    assert_eq!(chain.reply_id(), Some("1"));
+    assert_eq!(chain.images(), vec!["a.jpg"]);
+    assert_eq!(
+        chain.to_string(),
+        "[CQ:reply,id=1][CQ:at,qq=123] hello [CQ:at,qq=all]world[CQ:image,file=a.jpg]"
+    );
+}
@@ -0,0 +1,157 @@
+use super::{Message, MessageChain};
+
+/// QQ 单条合并转发最多包含的节点数
+pub const MAX_FORWARD_NODES: usize = 100;
+
+/// 合并转发构建器
+///
+/// 节点数或文本长度超出限制时拆分为多条合并转发
+///
+/// ```ignore
+/// let forward = ForwardBuilder::new()
+///     .node("10000", "排行榜", "1. Alice 100")
+///     .node("10000", "排行榜", "2. Bob 90")
+///     .message_id("123456");
+/// if let Err((sent, e)) = bot.send_group_forward("987654321", forward).await {
+///     println!("已发送 {} 条，发送失败：{}", sent.len(), e);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ForwardBuilder {
+    nodes: Vec<Message>,
+    max_nodes: usize,
+    max_text_len: usize,
+}
+
+impl Default for ForwardBuilder {
+    fn default() -> Self {
+        ForwardBuilder {
+            nodes: vec![],
+            max_nodes: MAX_FORWARD_NODES,
+            max_text_len: 0,
+        }
+    }
+}
+
+impl ForwardBuilder {
+    /// 新建空合并转发
+    pub fn new() -> Self {
+        ForwardBuilder::default()
+    }
+
+    /// 追加自定义节点，以 user_id 与 nickname 的身份显示 content
+    pub fn node<C>(mut self, user_id: &str, nickname: &str, content: C) -> Self
+    where
+        C: Into<MessageChain>,
+    {
+        self.nodes.push(Message::node(
+            None,
+            Some(user_id.to_string()),
+            Some(nickname.to_string()),
+            Some(content.into().into_vec()),
+        ));
+        self
+    }
+
+    /// 追加已有消息
+    pub fn message_id(mut self, message_id: &str) -> Self {
+        self.nodes.push(Message::node(
+            Some(message_id.to_string()),
+            None,
+            None,
+            None,
+        ));
+        self
+    }
+
+    /// 设置单条合并转发最多节点数，默认 `MAX_FORWARD_NODES`
+    pub fn max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = max_nodes.max(1);
+        self
+    }
+
+    /// 设置单条合并转发最大文本长度（字符数），0 为不限制
+    ///
+    /// 单个节点超出限制时独占一条合并转发
+    pub fn max_text_len(mut self, max_text_len: usize) -> Self {
+        self.max_text_len = max_text_len;
+        self
+    }
+
+    /// 节点数
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// 是否没有节点
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// 按限制拆分节点，每项为一条合并转发的 messages
+    pub fn build(self) -> Vec<Vec<Message>> {
+        let mut forwards = vec![];
+        let mut current: Vec<Message> = vec![];
+        let mut current_len = 0;
+        for node in self.nodes {
+            let len = text_len(&node);
+            let full = current.len() >= self.max_nodes
+                || (self.max_text_len > 0 && current_len + len > self.max_text_len);
+            if full && !current.is_empty() {
+                forwards.push(std::mem::take(&mut current));
+                current_len = 0;
+            }
+            current.push(node);
+            current_len += len;
+        }
+        if !current.is_empty() {
+            forwards.push(current);
+        }
+        forwards
+    }
+}
+
+/// 节点内容中的纯文本字符数
+fn text_len(node: &Message) -> usize {
+    match node {
+        Message::Node {
+            content: Some(content),
+            ..
+        } => content
+            .iter()
+            .map(|m| match m {
+                Message::Text { text } => text.chars().count(),
+                _ => 0,
+            })
+            .sum(),
+        _ => 0,
+    }
+}
+
+#[test]
+fn forward_builder_test() {
+    let mut forward = ForwardBuilder::new().max_nodes(2).message_id("1");
+    for i in 0..3 {
+        forward = forward.node("10000", "bot", format!("第{}名", i));
+    }
+    assert_eq!(forward.len(), 4);
+    let forwards = forward.build();
+    assert_eq!(
+        forwards.iter().map(|f| f.len()).collect::<Vec<_>>(),
+        vec![2, 2]
+    );
+    let json = serde_json::to_value(&forwards[0]).unwrap();
+    assert_eq!(json[0]["data"], serde_json::json!({ "id": "1" }));
+    assert_eq!(json[1]["data"]["nickname"], "bot");
+
+    let forwards = ForwardBuilder::new()
+        .max_text_len(5)
+        .node("10000", "bot", "一二三")
+        .node("10000", "bot", "四五六")
+        .node("10000", "bot", "超出限制的长节点")
+        .build();
+    assert_eq!(
+        forwards.iter().map(|f| f.len()).collect::<Vec<_>>(),
+        vec![1, 1, 1]
+    );
+}
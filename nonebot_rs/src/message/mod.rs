@@ -1,5 +1,11 @@
 use serde::{Deserialize, Serialize};
 
+mod chain;
+mod forward;
+
+pub use chain::MessageChain;
+pub use forward::{ForwardBuilder, MAX_FORWARD_NODES};
+
 /// Onebot 协议消息定义
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(tag = "type", content = "data")]
@@ -177,12 +183,16 @@ pub enum Message {
     #[serde(rename = "node")]
     Node {
         /// 转发的消息 ID
+        #[serde(skip_serializing_if = "Option::is_none")]
         id: Option<String>,
-        /// 发送者 QQ 号        
+        /// 发送者 QQ 号
+        #[serde(skip_serializing_if = "Option::is_none")]
         user_id: Option<String>,
-        /// 发送者昵称   
+        /// 发送者昵称
+        #[serde(skip_serializing_if = "Option::is_none")]
         nickname: Option<String>,
-        /// 消息内容     
+        /// 消息内容
+        #[serde(skip_serializing_if = "Option::is_none")]
         content: Option<Vec<Message>>,
     },
 
@@ -199,6 +209,13 @@ pub enum Message {
         /// 合并转发 ID
         data: String,
     },
+
+    /// 文件（Onebot v12）
+    #[serde(rename = "file")]
+    File {
+        /// 文件 ID
+        file_id: String,
+    },
 }
 
 macro_rules! message_builder {
@@ -302,4 +319,156 @@ impl Message {
     );
     message_builder!(xml, Xml, data: String);
     message_builder!(json, Json, data: String);
+    message_builder!(file, File, file_id: String);
+}
+
+/// CQ 码转义，`comma` 为 true 时同时转义 `,`（用于 CQ 码参数）
+pub fn cq_escape(s: &str, comma: bool) -> String {
+    let s = s
+        .replace('&', "&amp;")
+        .replace('[', "&#91;")
+        .replace(']', "&#93;");
+    if comma {
+        s.replace(',', "&#44;")
+    } else {
+        s
+    }
+}
+
+/// CQ 码反转义
+pub fn cq_unescape(s: &str) -> String {
+    s.replace("&#44;", ",")
+        .replace("&#91;", "[")
+        .replace("&#93;", "]")
+        .replace("&amp;", "&")
+}
+
+/// CQ 码中需要转为数字的参数
+const CQ_NUMBER_KEYS: [&str; 4] = ["cache", "proxy", "timeout", "magic"];
+
+impl Message {
+    /// 转为 CQ 码，纯文本转义后直接返回
+    pub fn to_cq_code(&self) -> String {
+        if let Message::Text { text } = self {
+            return cq_escape(text, false);
+        }
+        let value = serde_json::to_value(self).unwrap();
+        let mut cq = format!("[CQ:{}", value["type"].as_str().unwrap_or_default());
+        if let Some(data) = value["data"].as_object() {
+            for (key, value) in data {
+                let value = match value {
+                    serde_json::Value::Null => continue,
+                    serde_json::Value::String(s) => s.clone(),
+                    value => value.to_string(),
+                };
+                cq.push_str(&format!(",{}={}", key, cq_escape(&value, true)));
+            }
+        }
+        cq.push(']');
+        cq
+    }
+
+    /// 解析单个 CQ 码（不含 `[CQ:` 与 `]`），无法识别的 CQ 码返回 None
+    fn from_cq_code(code: &str) -> Option<Message> {
+        let mut params = code.split(',');
+        let type_ = params.next()?;
+        let mut data = serde_json::Map::new();
+        for param in params {
+            let mut kv = param.splitn(2, '=');
+            let key = kv.next()?;
+            let value = cq_unescape(kv.next().unwrap_or_default());
+            let value = match value.parse::<i64>() {
+                Ok(n) if CQ_NUMBER_KEYS.contains(&key) => serde_json::json!(n),
+                _ => serde_json::Value::String(value),
+            };
+            data.insert(key.to_string(), value);
+        }
+        let value = if data.is_empty() {
+            serde_json::json!({ "type": type_ })
+        } else {
+            serde_json::json!({ "type": type_, "data": data })
+        };
+        serde_json::from_value(value).ok()
+    }
+}
+
+/// 消息数组转为 CQ 码字符串
+pub fn to_cq_string(messages: &[Message]) -> String {
+    messages.iter().map(Message::to_cq_code).collect()
+}
+
+/// CQ 码字符串转为消息数组，无法识别的 CQ 码保留为纯文本
+pub fn from_cq_string(s: &str) -> Vec<Message> {
+    let mut messages = vec![];
+    let push_text = |messages: &mut Vec<Message>, text: &str| {
+        if !text.is_empty() {
+            messages.push(Message::text(cq_unescape(text)));
+        }
+    };
+    let mut rest = s;
+    while let Some(start) = rest.find("[CQ:") {
+        let end = match rest[start..].find(']') {
+            Some(end) => start + end,
+            None => break,
+        };
+        push_text(&mut messages, &rest[..start]);
+        let code = &rest[start + 4..end];
+        match Message::from_cq_code(code) {
+            Some(message) => messages.push(message),
+            None => messages.push(Message::text(rest[start..=end].to_string())),
+        }
+        rest = &rest[end + 1..];
+    }
+    push_text(&mut messages, rest);
+    messages
+}
+
+/// 转换字符串消息，auto_escape 为 true 时作为纯文本，否则解析 CQ 码
+pub fn from_string(s: &str, auto_escape: bool) -> Vec<Message> {
+    if auto_escape {
+        vec![Message::text(s.to_string())]
+    } else {
+        from_cq_string(s)
+    }
+}
+
+/// 同时支持 array 与 CQ 码 string 格式上报的消息反序列化
+pub fn message_deserializer<'de, D>(d: D) -> Result<Vec<Message>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum MessageFormat {
+        Array(Vec<Message>),
+        String(String),
+    }
+    Ok(match MessageFormat::deserialize(d)? {
+        MessageFormat::Array(messages) => messages,
+        MessageFormat::String(s) => from_cq_string(&s),
+    })
+}
+
+#[test]
+fn cq_code_test() {
+    let raw = "[CQ:at,qq=123] hello&#91;world&#93; [CQ:image,file=a&#44;b.jpg,cache=0][CQ:rps]";
+    let messages = from_cq_string(raw);
+    assert_eq!(messages.len(), 4);
+    match &messages[1] {
+        Message::Text { text } => assert_eq!(text, " hello[world] "),
+        m => panic!("unexpected {:?}", m),
+    }
+    match &messages[2] {
+        Message::Image { file, cache, .. } => {
+            assert_eq!(file, "a,b.jpg");
+            assert_eq!(cache, &Some(0));
+        }
+        m => panic!("unexpected {:?}", m),
+    }
+    let cq = to_cq_string(&messages);
+    assert!(cq.starts_with("[CQ:at,qq=123] hello&#91;world&#93; [CQ:image,"));
+    assert_eq!(
+        format!("{:?}", from_cq_string(&cq)),
+        format!("{:?}", messages)
+    );
 }
@@ -1,5 +1,9 @@
-use crate::{ActionSender, ApiChannelItem, ApiResp, Bot, Nonebot, Plugin};
+use crate::{
+    Action, ActionSender, ApiChannelItem, ApiRespPending, Bot, Nonebot, Plugin, ShutdownHandle,
+};
 use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
 use tokio::sync::{broadcast, mpsc, watch};
 
 impl Nonebot {
@@ -9,14 +13,15 @@ impl Nonebot {
         bot_id: String,
         api_sender: mpsc::Sender<ApiChannelItem>,
         action_sender: ActionSender,
-        api_resp_watcher: watch::Receiver<ApiResp>,
+        api_resp_pending: ApiRespPending,
     ) -> Bot {
         let bot = Bot::new(
             bot_id.clone(),
             self.config.gen_bot_config(&bot_id),
             api_sender,
             action_sender,
-            api_resp_watcher,
+            api_resp_pending,
+            self.storage.clone(),
         );
         self.bots.insert(bot_id.to_string(), bot.clone());
         self.bot_sender.send(self.bots.clone()).unwrap();
@@ -37,6 +42,8 @@ impl Nonebot {
         let (event_sender, _) = broadcast::channel(1024); // need largo cache when reconnect
         let (action_sender, action_receiver) = tokio::sync::mpsc::channel(32);
         let (bot_sender, bot_getter) = watch::channel(HashMap::new());
+        let (shutdown_sender, shutdown_receiver) = watch::channel(false);
+        let storage = Arc::new(crate::storage::FileStorage::new(&nb_config.global.data_dir));
         Nonebot {
             bots: HashMap::new(),
             config: nb_config,
@@ -46,9 +53,25 @@ impl Nonebot {
             bot_sender,
             bot_getter,
             plugins: HashMap::new(),
+            shutdown_sender: Arc::new(shutdown_sender),
+            shutdown_receiver,
+            shutdown_timeout: std::time::Duration::from_secs(10),
+            storage,
         }
     }
 
+    /// 获取 Nonebot 关闭句柄
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        ShutdownHandle {
+            sender: self.shutdown_sender.clone(),
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn shutdown_receiver(&self) -> crate::ShutdownReceiver {
+        self.shutdown_receiver.clone()
+    }
+
     /// 添加 Plugin
     pub fn add_plugin<P>(&mut self, p: P)
     where
@@ -93,10 +116,64 @@ impl Nonebot {
         }
     }
 
-    /// Nonebot EventChannel receive handle
-    async fn recv(mut self) {
-        while let Some(action) = self.action_receiver.recv().await {
-            self.handle_action(action)
+    /// Nonebot EventChannel receive handle，收到关闭信号后返回
+    async fn recv<F>(&mut self, signal: F)
+    where
+        F: Future,
+    {
+        tokio::pin!(signal);
+        let mut shutdown_receiver = self.shutdown_receiver.clone();
+        loop {
+            tokio::select! {
+                action = self.action_receiver.recv() => match action {
+                    Some(action) => self.handle_action(action),
+                    None => break,
+                },
+                _ = &mut signal => break,
+                _ = shutdown_receiver.changed() => break,
+            }
+        }
+    }
+
+    /// 关闭所有连接，移除所有 Bot 并等待所有 Plugin `on_shutdown`
+    async fn shutdown(&mut self) {
+        use colored::*;
+        tracing::event!(tracing::Level::INFO, "{}", "Nonebot is shutting down".red());
+        self.shutdown_sender.send(true).ok();
+
+        let bots: Vec<(String, ApiRespPending)> = self
+            .bots
+            .iter()
+            .map(|(bot_id, bot)| (bot_id.clone(), bot.api_resp_pending.clone()))
+            .collect();
+        for (bot_id, api_resp_pending) in bots {
+            self.handle_action(Action::RemoveBot {
+                bot_id,
+                api_resp_pending,
+            });
+        }
+
+        let shutdown_timeout = self.shutdown_timeout;
+        let plugins = &mut self.plugins;
+        let shutdown_plugins = async move {
+            for (plugin_name, plugin) in plugins.iter_mut() {
+                plugin.on_shutdown().await;
+                tracing::event!(
+                    tracing::Level::INFO,
+                    "Plugin {} is shutdown.",
+                    plugin_name.red()
+                );
+            }
+        };
+        if tokio::time::timeout(shutdown_timeout, shutdown_plugins)
+            .await
+            .is_err()
+        {
+            tracing::event!(
+                tracing::Level::WARN,
+                "Plugins shutdown timeout after {:?}",
+                shutdown_timeout
+            );
         }
     }
 
@@ -106,24 +183,28 @@ impl Nonebot {
         self.async_run().await;
     }
 
+    /// 运行 Nonebot 实例，signal 完成或调用 `ShutdownHandle::shutdown` 后关闭 Nonebot
+    #[tokio::main]
+    pub async fn run_until<F>(self, signal: F)
+    where
+        F: Future,
+    {
+        self.async_run_until(signal).await;
+    }
+
     #[doc(hidden)]
-    pub async fn async_run(mut self) {
+    pub async fn async_run(self) {
+        self.async_run_until(std::future::pending::<()>()).await;
+    }
+
+    #[doc(hidden)]
+    pub async fn async_run_until<F>(mut self, signal: F)
+    where
+        F: Future,
+    {
         self.pre_run().await;
-        // let access_tokens = self.config.gen_access_token();
-        // tokio::spawn(crate::comms::revs_ws::run(
-        //     self.config.global.host,
-        //     self.config.global.port,
-        //     self.event_sender.clone(),
-        //     self.action_sender.clone(),
-        //     access_tokens.clone(),
-        // ));
-        // tokio::spawn(crate::comms::ws::run(
-        //     vec!["ws://127.0.0.1:6700/ws"],
-        //     self.event_sender.clone(),
-        //     self.action_sender.clone(),
-        //     access_tokens,
-        // ));
         crate::comms::strat_comms(&self).await;
-        self.recv().await;
+        self.recv(signal).await;
+        self.shutdown().await;
     }
 }
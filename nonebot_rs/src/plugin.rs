@@ -3,7 +3,7 @@ use async_trait::async_trait;
 
 /// Prelude for Plugin
 pub mod prelude {
-    pub use super::Plugin;
+    pub use super::{recv_event, Plugin};
     pub use crate::event::{Event, MessageEvent, NbEvent};
     pub use crate::event::{SelfId, UserId};
     pub use crate::message::Message;
@@ -11,6 +11,20 @@ pub mod prelude {
     pub use toml;
 }
 
+/// 从 EventReceiver 接收 Event，处理过慢丢失的 Event 将被跳过，channel 关闭时返回 None
+pub async fn recv_event(event_receiver: &mut crate::EventReceiver) -> Option<crate::event::Event> {
+    use tokio::sync::broadcast::error::RecvError;
+    loop {
+        match event_receiver.recv().await {
+            Ok(event) => return Some(event),
+            Err(RecvError::Lagged(n)) => {
+                tracing::event!(tracing::Level::WARN, "Plugin lagged, skipped {} events", n);
+            }
+            Err(RecvError::Closed) => return None,
+        }
+    }
+}
+
 /// A trait for nbrs plugins
 #[async_trait]
 pub trait Plugin: std::fmt::Debug {
@@ -21,4 +35,6 @@ pub trait Plugin: std::fmt::Debug {
     /// Load config
     #[allow(unused_variables)]
     async fn load_config(&mut self, config: toml::Value);
+    /// Nonebot 关闭时调用，用于保存状态、等待处理中的任务
+    async fn on_shutdown(&mut self) {}
 }
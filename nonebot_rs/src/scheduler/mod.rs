@@ -3,6 +3,8 @@ use crate::log::{colored::*, event, Level};
 use async_trait::async_trait;
 use serde::Deserialize;
 use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::task::JoinHandle;
 use tokio_cron_scheduler::{Job, JobScheduler};
 
 /// Prelude for Scheduler Plugin
@@ -15,6 +17,7 @@ pub mod prelude {
 pub struct Scheduler {
     scheduler: JobScheduler,
     config: SchedulerConfig,
+    handle: Mutex<Option<JoinHandle<()>>>,
 }
 
 impl std::fmt::Debug for Scheduler {
@@ -45,7 +48,7 @@ pub struct JobConfig {
 impl crate::Plugin for Scheduler {
     fn run(&self, _: crate::EventReceiver, _: crate::BotGetter) {
         if !self.config.disable {
-            tokio::spawn(self.scheduler.start());
+            *self.handle.lock().unwrap() = Some(self.scheduler.start());
         }
     }
 
@@ -62,6 +65,12 @@ impl crate::Plugin for Scheduler {
             self.config
         );
     }
+
+    async fn on_shutdown(&mut self) {
+        if let Some(handle) = self.handle.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
 }
 
 impl Scheduler {
@@ -72,6 +81,7 @@ impl Scheduler {
                 disable: false,
                 jobs: HashMap::new(),
             },
+            handle: Mutex::new(None),
         }
     }
 
@@ -0,0 +1,267 @@
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// 默认数据目录
+pub static DEFAULT_DATA_DIR: &str = "data";
+
+/// 存储错误
+#[derive(Debug)]
+pub enum StorageError {
+    /// 读写失败
+    Io(std::io::Error),
+    /// 序列化或反序列化失败
+    Serde(serde_json::Error),
+}
+
+impl std::fmt::Display for StorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StorageError::Io(e) => write!(f, "Storage io error: {}", e),
+            StorageError::Serde(e) => write!(f, "Storage serde error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<std::io::Error> for StorageError {
+    fn from(e: std::io::Error) -> Self {
+        StorageError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for StorageError {
+    fn from(e: serde_json::Error) -> Self {
+        StorageError::Serde(e)
+    }
+}
+
+/// 键值存储后端
+///
+/// 以 namespace 隔离各 Plugin 与 Matcher 的数据
+#[async_trait]
+pub trait Storage: std::fmt::Debug + Send + Sync {
+    /// 读取
+    async fn get(&self, namespace: &str, key: &str) -> Result<Option<Value>, StorageError>;
+    /// 写入
+    async fn set(&self, namespace: &str, key: &str, value: Value) -> Result<(), StorageError>;
+    /// 删除
+    async fn delete(&self, namespace: &str, key: &str) -> Result<(), StorageError>;
+    /// 按 key 前缀遍历
+    async fn scan(
+        &self,
+        namespace: &str,
+        prefix: &str,
+    ) -> Result<Vec<(String, Value)>, StorageError>;
+}
+
+/// 共享存储后端
+pub type SharedStorage = Arc<dyn Storage>;
+
+/// 基于 JSON 文件的存储，每个 namespace 对应数据目录下一个文件
+#[derive(Debug, Clone)]
+pub struct FileStorage {
+    dir: PathBuf,
+    cache: Arc<Mutex<HashMap<String, BTreeMap<String, Value>>>>,
+}
+
+impl Default for FileStorage {
+    fn default() -> Self {
+        FileStorage::new(DEFAULT_DATA_DIR)
+    }
+}
+
+impl FileStorage {
+    /// 以 dir 为数据目录新建存储
+    pub fn new<P>(dir: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        FileStorage {
+            dir: dir.into(),
+            cache: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// namespace 对应的文件路径
+    ///
+    /// 小写字母、数字与 `-` 原样保留，其余字节转义为 `_xx`，保证不同 namespace 不会共用文件
+    fn path(&self, namespace: &str) -> PathBuf {
+        let mut name = String::with_capacity(namespace.len());
+        for b in namespace.bytes() {
+            if b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-' {
+                name.push(b as char);
+            } else {
+                name.push_str(&format!("_{:02x}", b));
+            }
+        }
+        self.dir.join(format!("{}.json", name))
+    }
+
+    async fn load<'a>(
+        &self,
+        cache: &'a mut HashMap<String, BTreeMap<String, Value>>,
+        namespace: &str,
+    ) -> Result<&'a mut BTreeMap<String, Value>, StorageError> {
+        if !cache.contains_key(namespace) {
+            let data = match tokio::fs::read(self.path(namespace)).await {
+                Ok(data) => serde_json::from_slice(&data)?,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+                Err(e) => return Err(e.into()),
+            };
+            cache.insert(namespace.to_string(), data);
+        }
+        Ok(cache.get_mut(namespace).unwrap())
+    }
+
+    async fn save(
+        &self,
+        namespace: &str,
+        data: &BTreeMap<String, Value>,
+    ) -> Result<(), StorageError> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let path = self.path(namespace);
+        // 先写入临时文件再替换，避免写入中断损坏数据
+        let tmp = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp, serde_json::to_vec_pretty(data)?).await?;
+        tokio::fs::rename(&tmp, &path).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Storage for FileStorage {
+    async fn get(&self, namespace: &str, key: &str) -> Result<Option<Value>, StorageError> {
+        let mut cache = self.cache.lock().await;
+        Ok(self.load(&mut cache, namespace).await?.get(key).cloned())
+    }
+
+    async fn set(&self, namespace: &str, key: &str, value: Value) -> Result<(), StorageError> {
+        let mut cache = self.cache.lock().await;
+        let data = self.load(&mut cache, namespace).await?;
+        data.insert(key.to_string(), value);
+        self.save(namespace, data).await
+    }
+
+    async fn delete(&self, namespace: &str, key: &str) -> Result<(), StorageError> {
+        let mut cache = self.cache.lock().await;
+        let data = self.load(&mut cache, namespace).await?;
+        if data.remove(key).is_some() {
+            self.save(namespace, data).await?;
+        }
+        Ok(())
+    }
+
+    async fn scan(
+        &self,
+        namespace: &str,
+        prefix: &str,
+    ) -> Result<Vec<(String, Value)>, StorageError> {
+        let mut cache = self.cache.lock().await;
+        Ok(self
+            .load(&mut cache, namespace)
+            .await?
+            .range(prefix.to_string()..)
+            .take_while(|(k, _)| k.starts_with(prefix))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect())
+    }
+}
+
+/// 限定 namespace 的存储句柄，以 serde 类型读写
+#[derive(Debug, Clone)]
+pub struct Namespace {
+    storage: SharedStorage,
+    namespace: String,
+}
+
+impl Namespace {
+    pub fn new(storage: SharedStorage, namespace: &str) -> Self {
+        Namespace {
+            storage,
+            namespace: namespace.to_string(),
+        }
+    }
+
+    /// 读取，不存在时返回 None
+    pub async fn get<T>(&self, key: &str) -> Result<Option<T>, StorageError>
+    where
+        T: DeserializeOwned,
+    {
+        match self.storage.get(&self.namespace, key).await? {
+            Some(value) => Ok(Some(serde_json::from_value(value)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// 写入
+    pub async fn set<T>(&self, key: &str, value: &T) -> Result<(), StorageError>
+    where
+        T: Serialize,
+    {
+        let value = serde_json::to_value(value)?;
+        self.storage.set(&self.namespace, key, value).await
+    }
+
+    /// 删除
+    pub async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        self.storage.delete(&self.namespace, key).await
+    }
+
+    /// 按 key 前缀遍历
+    pub async fn scan<T>(&self, prefix: &str) -> Result<Vec<(String, T)>, StorageError>
+    where
+        T: DeserializeOwned,
+    {
+        self.storage
+            .scan(&self.namespace, prefix)
+            .await?
+            .into_iter()
+            .map(|(k, v)| Ok((k, serde_json::from_value(v)?)))
+            .collect()
+    }
+}
+
+#[tokio::test]
+async fn file_storage_test() {
+    let dir = std::env::temp_dir().join(format!("nbrs-storage-{}", crate::utils::gen_echo("test")));
+    let storage: SharedStorage = Arc::new(FileStorage::new(&dir));
+    let ns = Namespace::new(storage, "matcher.echo");
+    ns.set("group.1", &true).await.unwrap();
+    ns.set("group.2", &false).await.unwrap();
+    ns.set("user.1", &1).await.unwrap();
+    ns.delete("group.2").await.unwrap();
+
+    // 重新打开后数据依然存在
+    let ns = Namespace::new(Arc::new(FileStorage::new(&dir)), "matcher.echo");
+    assert_eq!(ns.get::<bool>("group.1").await.unwrap(), Some(true));
+    assert_eq!(ns.get::<bool>("group.2").await.unwrap(), None);
+    assert_eq!(
+        ns.scan::<bool>("group.").await.unwrap(),
+        vec![("group.1".to_string(), true)]
+    );
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn file_storage_path_test() {
+    let storage = FileStorage::new("data");
+    let names = [
+        "matcher.echo",
+        "matcher_echo",
+        "matcher/echo",
+        "Matcher.echo",
+    ];
+    let paths: std::collections::HashSet<_> = names.iter().map(|n| storage.path(n)).collect();
+    assert_eq!(paths.len(), names.len());
+    assert_eq!(
+        storage.path("matcher.echo"),
+        PathBuf::from("data").join("matcher_2eecho.json")
+    );
+}
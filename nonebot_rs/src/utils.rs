@@ -17,6 +17,20 @@ pub fn timestamp() -> i64 {
     time.timestamp()
 }
 
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static ECHO_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// 生成唯一 Api echo
+pub fn gen_echo(api_type: &str) -> String {
+    format!(
+        "{}-{}-{}",
+        api_type,
+        timestamp(),
+        ECHO_SEQ.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
 use serde::Deserializer;
 
 struct JsonIdVisitor;
@@ -80,3 +94,12 @@ where
 {
     d.deserialize_option(OptionJsonIdVisitor)
 }
+
+/// 读取未解析 json 中的 id，与 `id_deserializer` 相同兼容整数与字符串
+pub fn value_id(value: &serde_json::Value) -> Option<std::borrow::Cow<'_, str>> {
+    match value {
+        serde_json::Value::String(s) => Some(s.as_str().into()),
+        serde_json::Value::Number(n) => Some(n.to_string().into()),
+        _ => None,
+    }
+}